@@ -0,0 +1,120 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::AitError;
+
+/// Claims carried by a signed authorization token: who it was issued to, which
+/// operations it permits (e.g. `"exec"`), and when it expires (unix seconds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub exp: u64,
+}
+
+/// Verifies a compact `header.payload.signature` bearer token (HMAC-SHA256 over
+/// `header.payload`, base64url without padding) against `signing_secret`, then checks
+/// that the claims haven't expired and cover `required_scope`. Mutating commands call
+/// this before doing anything observable; read-only commands never do.
+pub fn verify(token: &str, signing_secret: &str, required_scope: &str) -> Result<AuthClaims, AitError> {
+    let mut parts = token.split('.');
+    let (header, payload, signature, rest) = (parts.next(), parts.next(), parts.next(), parts.next());
+    let (header, payload, signature) = match (header, payload, signature, rest) {
+        (Some(h), Some(p), Some(s), None) => (h, p, s),
+        _ => return Err(malformed()),
+    };
+
+    let expected_signature = sign(&format!("{}.{}", header, payload), signing_secret)?;
+    if !constant_time_eq(signature.as_bytes(), expected_signature.as_bytes()) {
+        return Err(AitError::PermissionDenied { message: "Invalid token signature".to_string() });
+    }
+
+    let payload_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| malformed())?;
+    let claims: AuthClaims = serde_json::from_slice(&payload_bytes).map_err(|_| malformed())?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    if claims.exp <= now {
+        return Err(AitError::PermissionDenied { message: "Authorization token has expired".to_string() });
+    }
+
+    if !claims.scopes.iter().any(|s| s == required_scope) {
+        return Err(AitError::PermissionDenied {
+            message: format!("Token is not scoped for '{}'", required_scope),
+        });
+    }
+
+    Ok(claims)
+}
+
+fn malformed() -> AitError {
+    AitError::PermissionDenied { message: "Malformed authorization token".to_string() }
+}
+
+fn sign(signing_input: &str, signing_secret: &str) -> Result<String, AitError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes())
+        .map_err(|e| AitError::Internal { message: format!("Invalid signing key: {}", e) })?;
+    mac.update(signing_input.as_bytes());
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(secret: &str, scopes: &[&str], exp: u64) -> String {
+        let header = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256"}"#);
+        let claims = AuthClaims { sub: "test".to_string(), scopes: scopes.iter().map(|s| s.to_string()).collect(), exp };
+        let payload =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+        let signature = sign(&format!("{}.{}", header, payload), secret).unwrap();
+        format!("{}.{}.{}", header, payload, signature)
+    }
+
+    #[test]
+    fn verify_accepts_a_well_formed_token_in_scope() {
+        let token = issue("secret", &["exec"], now_plus(3600));
+        let claims = verify(&token, "secret", "exec").expect("token should verify");
+        assert_eq!(claims.sub, "test");
+    }
+
+    #[test]
+    fn verify_rejects_wrong_signing_secret() {
+        let token = issue("secret", &["exec"], now_plus(3600));
+        assert!(verify(&token, "other-secret", "exec").is_err());
+    }
+
+    #[test]
+    fn verify_rejects_expired_token() {
+        let token = issue("secret", &["exec"], now_plus(0).saturating_sub(1));
+        assert!(verify(&token, "secret", "exec").is_err());
+    }
+
+    #[test]
+    fn verify_rejects_token_missing_required_scope() {
+        let token = issue("secret", &["search"], now_plus(3600));
+        assert!(verify(&token, "secret", "exec").is_err());
+    }
+
+    #[test]
+    fn verify_rejects_malformed_token() {
+        assert!(verify("not-a-token", "secret", "exec").is_err());
+    }
+
+    fn now_plus(secs: u64) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() + secs
+    }
+}
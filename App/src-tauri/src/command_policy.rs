@@ -0,0 +1,150 @@
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::AppSettings;
+
+/// Returned when a command line is rejected by the whitelist/blacklist policy.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PolicyError {
+    pub reason: String,
+    /// The pattern that caused the rejection, empty when no whitelist pattern matched.
+    pub pattern: String,
+}
+
+/// Checks `command` against the configured blacklist and (if non-empty) whitelist.
+///
+/// Blacklist patterns are checked first so an explicit deny always wins, even if the
+/// same command would also match a whitelist pattern. Patterns are shell-style globs
+/// (e.g. `git *`, `rm -rf *`) matched against both the full command line and the
+/// program name alone, so a pattern can target either.
+///
+/// Matching runs against the command with runs of whitespace collapsed to a single
+/// space — the same normalization `process_runner`'s `split_whitespace()` tokenizer
+/// effectively applies before exec'ing — so a pattern like `"rm -rf *"` can't be
+/// dodged by inserting an extra space or a tab between tokens.
+pub fn check_command(settings: &AppSettings, command: &str) -> Result<(), PolicyError> {
+    let normalized = normalize_whitespace(command);
+    let program = normalized.split_whitespace().next().unwrap_or("");
+
+    if let Some(pattern) = matches_any(&settings.blacklisted_commands, &normalized, program) {
+        return Err(PolicyError {
+            reason: format!("Command '{}' is blocked by the blacklist", command),
+            pattern,
+        });
+    }
+
+    if !settings.whitelisted_commands.is_empty()
+        && matches_any(&settings.whitelisted_commands, &normalized, program).is_none()
+    {
+        return Err(PolicyError {
+            reason: format!("Command '{}' does not match any whitelisted pattern", command),
+            pattern: String::new(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Collapses any run of whitespace (spaces, tabs, newlines) into a single space, the
+/// same shape `str::split_whitespace()` imposes on a command before it's tokenized and
+/// exec'd, so pattern matching sees the command the same way the shell-out does.
+fn normalize_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn matches_any(patterns: &[String], command: &str, program: &str) -> Option<String> {
+    patterns.iter().find_map(|raw_pattern| {
+        let pattern = Pattern::new(raw_pattern).ok()?;
+        if pattern.matches(command) || pattern.matches(program) {
+            Some(raw_pattern.clone())
+        } else {
+            None
+        }
+    })
+}
+
+/// Checks `host` against the configured `http_fetch` host blacklist and (if non-empty)
+/// whitelist, the same blacklist-wins-then-whitelist-if-set shape as `check_command`.
+/// This is the caller-configurable policy layer; `http_fetch` also enforces a
+/// non-configurable block on internal/private addresses underneath it.
+pub fn check_host(settings: &AppSettings, host: &str) -> Result<(), PolicyError> {
+    let normalized = normalize_whitespace(host);
+
+    if let Some(pattern) = matches_any(&settings.blacklisted_fetch_hosts, &normalized, &normalized) {
+        return Err(PolicyError {
+            reason: format!("Host '{}' is blocked by the fetch blacklist", host),
+            pattern,
+        });
+    }
+
+    if !settings.whitelisted_fetch_hosts.is_empty()
+        && matches_any(&settings.whitelisted_fetch_hosts, &normalized, &normalized).is_none()
+    {
+        return Err(PolicyError {
+            reason: format!("Host '{}' does not match any whitelisted fetch host pattern", host),
+            pattern: String::new(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with(whitelist: &[&str], blacklist: &[&str]) -> AppSettings {
+        let mut settings = crate::commands::test_support::default_settings();
+        settings.whitelisted_commands = whitelist.iter().map(|s| s.to_string()).collect();
+        settings.blacklisted_commands = blacklist.iter().map(|s| s.to_string()).collect();
+        settings
+    }
+
+    #[test]
+    fn empty_whitelist_allows_anything_not_blacklisted() {
+        let settings = settings_with(&[], &["rm -rf *"]);
+        assert!(check_command(&settings, "ls -la").is_ok());
+    }
+
+    #[test]
+    fn blacklist_blocks_even_when_whitelisted() {
+        let settings = settings_with(&["git *"], &["git push *"]);
+        assert!(check_command(&settings, "git status").is_ok());
+        assert!(check_command(&settings, "git push origin main").is_err());
+    }
+
+    #[test]
+    fn non_empty_whitelist_rejects_unmatched_commands() {
+        let settings = settings_with(&["git *"], &[]);
+        assert!(check_command(&settings, "curl https://example.com").is_err());
+    }
+
+    #[test]
+    fn patterns_match_against_program_name_alone() {
+        let settings = settings_with(&[], &["rm"]);
+        assert!(check_command(&settings, "rm -rf /tmp/x").is_err());
+    }
+
+    #[test]
+    fn blacklist_cannot_be_dodged_with_extra_whitespace() {
+        let settings = settings_with(&[], &["rm -rf *"]);
+        assert!(check_command(&settings, "rm  -rf /").is_err());
+        assert!(check_command(&settings, "rm\t-rf /").is_err());
+    }
+
+    #[test]
+    fn check_host_blacklist_wins_over_whitelist() {
+        let mut settings = crate::commands::test_support::default_settings();
+        settings.whitelisted_fetch_hosts = vec!["*.example.com".to_string()];
+        settings.blacklisted_fetch_hosts = vec!["internal.example.com".to_string()];
+
+        assert!(check_host(&settings, "api.example.com").is_ok());
+        assert!(check_host(&settings, "internal.example.com").is_err());
+    }
+
+    #[test]
+    fn check_host_empty_whitelist_allows_any_non_blacklisted_host() {
+        let settings = crate::commands::test_support::default_settings();
+        assert!(check_host(&settings, "example.com").is_ok());
+    }
+}
@@ -1,13 +1,23 @@
-use std::process::Command;
-use std::path::Path;
 use std::fs::{self, create_dir_all}; // Added create_dir_all
 use std::path::PathBuf; // Added PathBuf
 use std::sync::Mutex;
 use tauri::{command, AppHandle, Manager}; // Added AppHandle, Manager
 use serde::{Deserialize, Serialize};
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
 use once_cell::sync::Lazy;
 
+use crate::auth;
+use crate::command_policy;
+use crate::error::AitError;
+use crate::fs_scan;
+use crate::http_fetch;
+use crate::key_store::{self, KeyHandle};
+use crate::persistence;
+use crate::process_runner;
+use crate::search_cache;
+use crate::search_provider;
+use crate::search_query::SearchQuery;
+use crate::telemetry;
+
 // Global settings storage
 static SETTINGS: Lazy<Mutex<Option<AppSettings>>> = Lazy::new(|| Mutex::new(None));
 // Global chats storage
@@ -15,19 +25,6 @@ static CHATS: Lazy<Mutex<Vec<RustSavedChat>>> = Lazy::new(|| Mutex::new(Vec::new
 // Global folders storage
 static FOLDERS: Lazy<Mutex<Vec<RustFolder>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct OsInfo {
-    pub os_type: String,
-    pub os_release: String,
-    pub hostname: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MemoryInfo {
-    pub total_mem: u64,
-    pub free_mem: u64,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
     pub openai_api_key: String,
@@ -48,22 +45,40 @@ pub struct AppSettings {
     // Command whitelist and blacklist
     pub whitelisted_commands: Vec<String>,
     pub blacklisted_commands: Vec<String>,
+    // When true, execute_command also requires a valid signed bearer token scoped to
+    // "exec", verified with auth_signing_secret, in addition to the whitelist/blacklist.
+    // http_fetch requires the same token scoped to "fetch".
+    #[serde(default)]
+    pub auth_required: bool,
+    #[serde(default)]
+    pub auth_signing_secret: String,
+    // http_fetch host whitelist/blacklist, matched the same way as
+    // whitelisted_commands/blacklisted_commands. Checked in addition to the
+    // non-configurable internal/private-address block in http_fetch.
+    #[serde(default)]
+    pub whitelisted_fetch_hosts: Vec<String>,
+    #[serde(default)]
+    pub blacklisted_fetch_hosts: Vec<String>,
     pub theme: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WebSearchResult {
     pub title: String,
     pub url: String,
     pub description: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WebSearchResponse {
     pub web: WebSearchWeb,
+    // Echoes the query's offset so the caller can request the next page by
+    // incrementing it, cursor-style.
+    #[serde(default)]
+    pub offset: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WebSearchWeb {
     pub results: Vec<WebSearchResult>,
 }
@@ -105,61 +120,37 @@ pub fn get_cwd() -> String {
 
 // Get OS information
 #[command]
-pub fn get_os_info() -> OsInfo {
-    let os_type = std::env::consts::OS.to_string();
-    
-    // Get OS release info (platform-specific)
-    let os_release = match std::env::consts::OS {
-        "windows" => {
-            std::env::var("OS").unwrap_or_else(|_| "Windows".to_string())
-        },
-        "macos" => {
-            Command::new("sw_vers")
-                .arg("-productVersion")
-                .output()
-                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
-                .unwrap_or_else(|_| "macOS".to_string())
-        },
-        "linux" => {
-            Command::new("cat")
-                .arg("/etc/os-release")
-                .output()
-                .map(|output| {
-                    let output_str = String::from_utf8_lossy(&output.stdout);
-                    output_str
-                        .lines()
-                        .find(|line| line.starts_with("PRETTY_NAME="))
-                        .and_then(|line| line.split('=').nth(1))
-                        .map(|name| name.trim_matches('"').to_string())
-                        .unwrap_or_else(|| "Linux".to_string())
-                })
-                .unwrap_or_else(|_| "Linux".to_string())
-        },
-        _ => "Unknown".to_string(),
-    };
-    
-    // Get hostname
-    let hostname = Command::new(if cfg!(target_os = "windows") { "hostname" } else { "hostname" })
-        .output()
-        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
-        .unwrap_or_else(|_| "Unknown".to_string());
-    
-    OsInfo {
-        os_type,
-        os_release,
-        hostname,
-    }
+pub fn get_os_info() -> telemetry::OsInfo {
+    telemetry::os_info()
 }
 
 // Get memory information
 #[command]
-pub fn get_memory_info() -> MemoryInfo {
-    // This is a simplified implementation
-    // For a real app, you might want to use a crate like sysinfo
-    MemoryInfo {
-        total_mem: 16 * 1024 * 1024, // 16 GB in KB (placeholder)
-        free_mem: 8 * 1024 * 1024,   // 8 GB in KB (placeholder)
-    }
+pub fn get_memory_info() -> telemetry::MemoryInfo {
+    telemetry::snapshot().memory
+}
+
+// Get a full one-shot telemetry snapshot (memory, CPU load, thread count)
+#[command]
+pub fn get_telemetry_snapshot() -> telemetry::Telemetry {
+    telemetry::snapshot()
+}
+
+// Start the background telemetry sampler, which emits snapshots to the frontend via
+// the `telemetry://sample` event until `stop_telemetry_sampler` is called or a repeat
+// call replaces it.
+#[command]
+pub fn start_telemetry_sampler(app_handle: AppHandle, interval_ms: Option<u64>) {
+    let interval = interval_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(telemetry::DEFAULT_SAMPLE_INTERVAL);
+    telemetry::start_sampler(app_handle, interval);
+}
+
+// Stop the background telemetry sampler started by `start_telemetry_sampler`, if any.
+#[command]
+pub fn stop_telemetry_sampler() {
+    telemetry::stop_sampler();
 }
 
 // Create default settings
@@ -167,18 +158,22 @@ fn create_default_settings() -> AppSettings {
     let mut walkthrough_tools = std::collections::HashMap::new();
     walkthrough_tools.insert("command".to_string(), true);
     walkthrough_tools.insert("web_search".to_string(), true);
-    
+    walkthrough_tools.insert("file_scan".to_string(), true);
+
     let mut action_tools = std::collections::HashMap::new();
     action_tools.insert("command".to_string(), true);
     action_tools.insert("web_search".to_string(), true);
-    
+    action_tools.insert("file_scan".to_string(), true);
+
     let mut auto_approve_walkthrough = std::collections::HashMap::new();
     auto_approve_walkthrough.insert("command".to_string(), false);
     auto_approve_walkthrough.insert("web_search".to_string(), false);
-    
+    auto_approve_walkthrough.insert("file_scan".to_string(), false);
+
     let mut auto_approve_action = std::collections::HashMap::new();
     auto_approve_action.insert("command".to_string(), false);
     auto_approve_action.insert("web_search".to_string(), false);
+    auto_approve_action.insert("file_scan".to_string(), false);
     
     AppSettings {
         openai_api_key: "".to_string(),
@@ -196,10 +191,23 @@ fn create_default_settings() -> AppSettings {
         auto_approve_action,
         whitelisted_commands: Vec::new(),
         blacklisted_commands: Vec::new(),
+        auth_required: false,
+        auth_signing_secret: "".to_string(),
+        whitelisted_fetch_hosts: Vec::new(),
+        blacklisted_fetch_hosts: Vec::new(),
         theme: "system".to_string(),
     }
 }
 
+/// Test-only access to this module's defaults, for other modules' unit tests that need
+/// an `AppSettings` (e.g. `command_policy`'s) without duplicating its field list.
+#[cfg(test)]
+pub(crate) mod test_support {
+    pub fn default_settings() -> super::AppSettings {
+        super::create_default_settings()
+    }
+}
+
 // Helper function to get the settings file path
 fn get_settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
     // Use ok_or_else to convert Option<PathBuf> to Result<PathBuf, String>
@@ -239,24 +247,24 @@ fn get_folders_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
     Ok(data_dir.join("folders.json"))
 }
 
-// Get settings
-#[command]
-pub fn get_settings(app_handle: AppHandle) -> AppSettings {
-    // Try to get settings from memory first
+// Loads settings the same way `get_settings` does (memory cache, then disk, then
+// defaults) without going through the `#[command]` boundary. Any caller that needs the
+// current settings — not just the frontend's explicit `get_settings` call — should go
+// through this so a fresh process that hasn't called `get_settings` yet still sees a
+// persisted whitelist/blacklist/`auth_required` instead of silently falling back to
+// wide-open defaults.
+fn load_or_default_settings(app_handle: &AppHandle) -> AppSettings {
     let mut settings_guard = SETTINGS.lock().unwrap();
 
     if let Some(settings) = settings_guard.as_ref() {
-        // Return a clone of the settings
         println!("Returning settings from memory cache.");
         return settings.clone();
     }
 
-    // If not in memory, try to load from file
-    let settings_path = match get_settings_path(&app_handle) {
+    let settings_path = match get_settings_path(app_handle) {
         Ok(path) => path,
         Err(e) => {
             eprintln!("Error getting settings path: {}", e);
-            // Fallback to default if path resolution fails
             let default_settings = create_default_settings();
             *settings_guard = Some(default_settings.clone());
             return default_settings;
@@ -264,27 +272,13 @@ pub fn get_settings(app_handle: AppHandle) -> AppSettings {
     };
 
     println!("Attempting to load settings from: {:?}", settings_path);
-    let settings = if settings_path.exists() {
-        match fs::read_to_string(&settings_path) {
-            Ok(json) => match serde_json::from_str::<AppSettings>(&json) {
-                Ok(loaded_settings) => {
-                    println!("Successfully loaded settings from file.");
-                    loaded_settings
-                },
-                Err(e) => {
-                    eprintln!("Failed to parse settings file: {}. Using defaults.", e);
-                    create_default_settings()
-                },
-            },
-            Err(e) => {
-                eprintln!("Failed to read settings file: {}. Using defaults.", e);
-                create_default_settings()
-            },
-        }
-    } else {
-        println!("Settings file not found. Using defaults.");
+    let settings = persistence::read_with_recovery(&settings_path, |json| {
+        serde_json::from_str::<AppSettings>(json).ok()
+    })
+    .unwrap_or_else(|| {
+        println!("Settings file not found or unrecoverable. Using defaults.");
         create_default_settings()
-    };
+    });
 
     // Store in memory for future use
     *settings_guard = Some(settings.clone());
@@ -292,6 +286,12 @@ pub fn get_settings(app_handle: AppHandle) -> AppSettings {
     settings
 }
 
+// Get settings
+#[command]
+pub fn get_settings(app_handle: AppHandle) -> AppSettings {
+    load_or_default_settings(&app_handle)
+}
+
 // Save settings
 #[command]
 pub fn save_settings(app_handle: AppHandle, settings: AppSettings) -> Result<(), String> {
@@ -303,7 +303,7 @@ pub fn save_settings(app_handle: AppHandle, settings: AppSettings) -> Result<(),
     let settings_path = get_settings_path(&app_handle)?; // Use helper function
 
     match serde_json::to_string_pretty(&settings) {
-        Ok(json) => match fs::write(&settings_path, json) { // Use the full path
+        Ok(json) => match persistence::write_atomic(&settings_path, &json) {
             Ok(_) => {
                 println!("Settings saved successfully to {:?}", settings_path);
                 Ok(())
@@ -336,27 +336,13 @@ pub fn get_all_chats(app_handle: AppHandle) -> Vec<RustSavedChat> {
     };
 
     println!("Attempting to load chats from: {:?}", chats_path);
-    let chats = if chats_path.exists() {
-        match fs::read_to_string(&chats_path) {
-            Ok(json) => match serde_json::from_str::<Vec<RustSavedChat>>(&json) {
-                Ok(loaded_chats) => {
-                    println!("Successfully loaded {} chats from file.", loaded_chats.len());
-                    loaded_chats
-                },
-                Err(e) => {
-                    eprintln!("Failed to parse chats file: {}. Using empty list.", e);
-                    Vec::new()
-                },
-            },
-            Err(e) => {
-                eprintln!("Failed to read chats file: {}. Using empty list.", e);
-                Vec::new()
-            },
-        }
-    } else {
-        println!("Chats file not found. Using empty list.");
+    let chats = persistence::read_with_recovery(&chats_path, |json| {
+        serde_json::from_str::<Vec<RustSavedChat>>(json).ok()
+    })
+    .unwrap_or_else(|| {
+        println!("Chats file not found or unrecoverable. Using empty list.");
         Vec::new()
-    };
+    });
 
     // Store in memory for future use
     *chats_guard = chats.clone();
@@ -394,7 +380,7 @@ pub fn save_chat(app_handle: AppHandle, chat: RustSavedChat) -> Result<(), Strin
     let chats_path = get_chats_path(&app_handle)?;
     
     match serde_json::to_string_pretty(&*chats_guard) {
-        Ok(json) => match fs::write(&chats_path, json) {
+        Ok(json) => match persistence::write_atomic(&chats_path, &json) {
             Ok(_) => {
                 println!("Chats saved successfully to {:?}", chats_path);
                 Ok(())
@@ -426,7 +412,7 @@ pub fn delete_chat(app_handle: AppHandle, chat_id: String) -> Result<(), String>
     let chats_path = get_chats_path(&app_handle)?;
     
     match serde_json::to_string_pretty(&*chats_guard) {
-        Ok(json) => match fs::write(&chats_path, json) {
+        Ok(json) => match persistence::write_atomic(&chats_path, &json) {
             Ok(_) => {
                 println!("Chats saved successfully after deletion to {:?}", chats_path);
                 Ok(())
@@ -459,27 +445,13 @@ pub fn get_all_folders(app_handle: AppHandle) -> Vec<RustFolder> {
     };
 
     println!("Attempting to load folders from: {:?}", folders_path);
-    let folders = if folders_path.exists() {
-        match fs::read_to_string(&folders_path) {
-            Ok(json) => match serde_json::from_str::<Vec<RustFolder>>(&json) {
-                Ok(loaded_folders) => {
-                    println!("Successfully loaded {} folders from file.", loaded_folders.len());
-                    loaded_folders
-                },
-                Err(e) => {
-                    eprintln!("Failed to parse folders file: {}. Using empty list.", e);
-                    Vec::new()
-                },
-            },
-            Err(e) => {
-                eprintln!("Failed to read folders file: {}. Using empty list.", e);
-                Vec::new()
-            },
-        }
-    } else {
-        println!("Folders file not found. Using empty list.");
+    let folders = persistence::read_with_recovery(&folders_path, |json| {
+        serde_json::from_str::<Vec<RustFolder>>(json).ok()
+    })
+    .unwrap_or_else(|| {
+        println!("Folders file not found or unrecoverable. Using empty list.");
         Vec::new()
-    };
+    });
 
     // Store in memory for future use
     *folders_guard = folders.clone();
@@ -535,7 +507,7 @@ pub fn save_folder(app_handle: AppHandle, folder: RustFolder) -> Result<(), Stri
     match serde_json::to_string_pretty(&*folders_guard) {
         Ok(json) => {
             println!("Serialized JSON: {}", json);
-            match fs::write(&folders_path, &json) {
+            match persistence::write_atomic(&folders_path, &json) {
                 Ok(_) => {
                     println!("Folders saved successfully to {:?}", folders_path);
                     // Verify the file was written
@@ -593,7 +565,7 @@ pub fn delete_folder(app_handle: AppHandle, folder_id: String) -> Result<(), Str
     match serde_json::to_string_pretty(&*folders_guard) {
         Ok(json) => {
             println!("Serialized JSON after deletion: {}", json);
-            match fs::write(&folders_path, &json) {
+            match persistence::write_atomic(&folders_path, &json) {
                 Ok(_) => {
                     println!("Folders saved successfully after deletion to {:?}", folders_path);
                     // Verify the file was written
@@ -615,99 +587,215 @@ pub fn delete_folder(app_handle: AppHandle, folder_id: String) -> Result<(), Str
     }
 }
 
-// Execute a command
+// Execute a command, streaming stdout/stderr to the frontend line-by-line as it runs
+// and resolving with the full captured output on exit. `run_id` is generated if the
+// caller doesn't supply one, and is what ties streamed events and cancel_command back
+// to this invocation.
 #[command]
-pub async fn execute_command(command: String, cwd: Option<String>) -> Result<String, String> {
+pub async fn execute_command(
+    app_handle: AppHandle,
+    command: String,
+    cwd: Option<String>,
+    run_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<String, AitError> {
     let cwd = cwd.unwrap_or_else(|| get_cwd());
-    
-    println!("Executing command: '{}' in directory: '{}'", command, cwd);
-    
-    // Split the command into program and arguments
-    let mut parts = command.split_whitespace();
-    let program = parts.next().ok_or_else(|| "Empty command".to_string())?;
-    let args: Vec<&str> = parts.collect();
-    
-    println!("Program: '{}', Args: {:?}", program, args);
-    
-    // Create the command
-    let mut cmd = Command::new(program);
-    cmd.args(args);
-    
-    // Set the working directory if it exists
-    if Path::new(&cwd).exists() {
-        cmd.current_dir(&cwd);
-        println!("Working directory set to: '{}'", cwd);
-    } else {
-        println!("Warning: Directory '{}' does not exist, using current directory", cwd);
+    let run_id = run_id.unwrap_or_else(process_runner::next_run_id);
+
+    // Enforce the configured whitelist/blacklist, and the signed-token policy if enabled,
+    // before spawning anything. Loaded the same way `get_settings` loads them, so a
+    // process that hasn't called `get_settings` yet still picks up a persisted policy
+    // instead of falling back to wide-open defaults.
+    {
+        let settings = load_or_default_settings(&app_handle);
+
+        if let Err(policy_error) = command_policy::check_command(&settings, &command) {
+            println!("Command blocked by policy: {}", policy_error.reason);
+            return Err(AitError::PermissionDenied {
+                message: format!("{} (pattern: {})", policy_error.reason, policy_error.pattern),
+            });
+        }
+
+        if settings.auth_required {
+            let token = auth_token.as_deref().ok_or_else(|| AitError::PermissionDenied {
+                message: "This instance requires a signed authorization token to run commands".to_string(),
+            })?;
+            auth::verify(token, &settings.auth_signing_secret, "exec")?;
+        }
     }
-    
-    // Execute the command
-    match cmd.output() {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            
-            println!("Command execution status: {}", output.status);
-            println!("stdout length: {}, stderr length: {}", stdout.len(), stderr.len());
-            
-            if !stderr.is_empty() {
-                if output.status.success() {
-                    Ok(format!("{}\n\nWarnings:\n{}", stdout, stderr))
-                } else {
-                    Err(format!("Command failed with error:\n{}", stderr))
-                }
-            } else if stdout.is_empty() && !output.status.success() {
-                Err(format!("Command failed with no output. Exit code: {}", output.status))
-            } else {
-                Ok(stdout)
-            }
-        },
+
+    // process_runner::run blocks its thread for the lifetime of the child process
+    // (it busy-polls try_wait() until exit). Run it on a blocking-pool thread instead
+    // of the async runtime's worker threads, the same way scan_directory offloads
+    // fs_scan::scan, so a few long-running commands can't starve the runtime and stall
+    // unrelated commands/events.
+    tauri::async_runtime::spawn_blocking(move || process_runner::run(app_handle, run_id, &command, &cwd))
+        .await
+        .map_err(|e| AitError::Internal { message: format!("Command task panicked: {}", e) })?
+}
+
+// Cancel an in-flight command started by execute_command, identified by its run id.
+#[command]
+pub fn cancel_command(run_id: String) -> Result<(), String> {
+    process_runner::cancel(&run_id)
+}
+
+/// Seals `secret` in the credential vault, scoped to `provider` and `scopes` (e.g.
+/// `["search"]`), with an optional unix-seconds expiry. Returns a handle describing the
+/// key, never the secret itself.
+#[command]
+pub fn create_key(
+    app_handle: AppHandle,
+    provider: String,
+    secret: String,
+    scopes: Vec<String>,
+    expires_at: Option<u64>,
+) -> Result<KeyHandle, AitError> {
+    key_store::create_key(&app_handle, provider, secret, scopes, expires_at)
+}
+
+/// Lists every key's metadata (provider, scopes, expiry, revocation status).
+#[command]
+pub fn list_keys(app_handle: AppHandle) -> Result<Vec<KeyHandle>, AitError> {
+    key_store::list_keys(&app_handle)
+}
+
+/// Revokes a key by id so future lookups treat it as gone, without deleting its audit entry.
+#[command]
+pub fn revoke_key(app_handle: AppHandle, key_id: String) -> Result<(), AitError> {
+    key_store::revoke_key(&app_handle, &key_id)
+}
+
+// Web search using the configured provider (default: Brave). The provider's API key, if
+// any, is looked up from the credential vault by provider id instead of being passed in.
+#[command]
+pub async fn web_search(
+    app_handle: AppHandle,
+    query: SearchQuery,
+    provider: Option<String>,
+    searxng_instance_url: Option<String>,
+    max_age_secs: Option<u64>,
+    offline: Option<bool>,
+) -> Result<WebSearchResponse, AitError> {
+    let max_age_secs = max_age_secs.unwrap_or(search_cache::DEFAULT_MAX_AGE_SECS);
+    let offline = offline.unwrap_or(false);
+
+    if let Some(cached) = search_cache::get_fresh(&app_handle, &query, max_age_secs, offline) {
+        println!("Returning cached search results for query: '{}'", query.q);
+        return Ok(cached);
+    }
+
+    let search_response = dispatch_search(&app_handle, &query, provider, searxng_instance_url).await;
+    let search_response = match search_response {
+        Ok(response) => response,
         Err(e) => {
-            println!("Command execution error: {}", e);
-            
-            // Provide more helpful error message for common issues
-            let error_msg = e.to_string();
-            if error_msg.contains("not found") || error_msg.contains("No such file or directory") {
-                Err(format!("Program '{}' not found. Make sure it is installed and in your system PATH.", program))
-            } else if error_msg.contains("permission denied") {
-                Err(format!("Permission denied when trying to execute '{}'. Check file permissions.", program))
-            } else {
-                Err(format!("Failed to execute command: {}", e))
+            if let Some(cached) = search_cache::get_fresh(&app_handle, &query, max_age_secs, true) {
+                println!("Search failed ({}); falling back to stale cached results for: '{}'", e, query.q);
+                return Ok(cached);
             }
-        },
-    }
+            return Err(e);
+        }
+    };
+
+    search_cache::store(&app_handle, &query, &search_response)?;
+    Ok(search_response)
 }
 
-// Web search using Brave Search API
+/// Bypasses the cache and forces a fresh lookup, storing the result for future calls.
 #[command]
-pub async fn web_search(query: String, limit: Option<u32>, api_key: String) -> Result<WebSearchResponse, String> {
-    if api_key.is_empty() {
-        return Err("Brave Search API key is not set".to_string());
-    }
-    
-    let limit = limit.unwrap_or(5);
-    let url = format!("https://api.search.brave.com/res/v1/web/search?q={}&count={}", 
-        urlencoding::encode(&query), limit);
-    
-    let client = reqwest::Client::new();
-    let mut headers = HeaderMap::new();
-    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", api_key))
-        .map_err(|e| format!("Invalid API key: {}", e))?);
-    
-    let response = client.get(&url)
-        .headers(headers)
-        .send()
+pub async fn force_refresh_search(
+    app_handle: AppHandle,
+    query: SearchQuery,
+    provider: Option<String>,
+    searxng_instance_url: Option<String>,
+) -> Result<WebSearchResponse, AitError> {
+    let search_response = dispatch_search(&app_handle, &query, provider, searxng_instance_url).await?;
+    search_cache::store(&app_handle, &query, &search_response)?;
+    Ok(search_response)
+}
+
+/// Removes cache entries older than `max_age_secs` (default: the same TTL as `web_search`).
+#[command]
+pub fn clear_expired_search_cache(app_handle: AppHandle, max_age_secs: Option<u64>) -> Result<usize, String> {
+    search_cache::clear_expired(&app_handle, max_age_secs.unwrap_or(search_cache::DEFAULT_MAX_AGE_SECS))
+}
+
+/// Runs the requested `provider` (default: Brave) and falls back to scraping DuckDuckGo's
+/// results page when it errors or returns nothing. Providers that need an API key (only
+/// Brave, today) have it resolved from the credential vault, scoped to the "search" action.
+async fn dispatch_search(
+    app_handle: &AppHandle,
+    query: &SearchQuery,
+    provider: Option<String>,
+    searxng_instance_url: Option<String>,
+) -> Result<WebSearchResponse, AitError> {
+    let provider_name = provider.unwrap_or_else(|| "brave".to_string());
+    let searxng_instance_url = searxng_instance_url.unwrap_or_default();
+
+    // A missing key is not fatal here: fall through with an empty key so the provider's
+    // own request fails and `search_with_fallback` below catches that error and retries
+    // against the scrape fallback, instead of hard-failing before fallback gets a chance.
+    let api_key = match provider_name.as_str() {
+        "brave" => key_store::resolve(app_handle, "brave", "search").unwrap_or_default(),
+        _ => String::new(),
+    };
+
+    let primary = search_provider::provider_for(&provider_name, &api_key, &searxng_instance_url);
+    let fallback = search_provider::ScrapeHtmlProvider::duckduckgo();
+
+    search_provider::search_with_fallback(primary.as_ref(), &fallback, query).await
+}
+
+// Recursively scan a directory for files, with extension/pattern filters and duplicate
+// detection by content hash. Progress is streamed via the `file_scan://progress` event.
+#[command]
+pub async fn scan_directory(app_handle: AppHandle, options: fs_scan::ScanOptions) -> Result<fs_scan::ScanResult, String> {
+    tauri::async_runtime::spawn_blocking(move || fs_scan::scan(app_handle, options))
         .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("API request failed with status: {}", response.status()));
+        .map_err(|e| format!("File scan task panicked: {}", e))?
+}
+
+// Cancel an in-flight directory scan started by scan_directory, identified by its run id.
+#[command]
+pub fn cancel_file_scan(run_id: String) -> Result<(), String> {
+    fs_scan::cancel(&run_id)
+}
+
+/// Generic outbound HTTP request for tool use, beyond the fixed set of search providers.
+/// Supports a redirect limit, timeout, response-size cap, and optional HTTP Message
+/// Signatures (the signing key is the credential-vault entry whose provider id equals
+/// `key_id`, scoped to the "sign" action, rather than being passed in).
+///
+/// Gated the same way as `execute_command`: the configured fetch host whitelist/
+/// blacklist is checked before anything is sent, and a signed token scoped to "fetch" is
+/// required when `auth_required` is set. `http_fetch` itself additionally refuses
+/// internal/private addresses unconditionally, regardless of this policy.
+#[command]
+pub async fn http_fetch(
+    app_handle: AppHandle,
+    request: http_fetch::FetchRequest,
+    auth_token: Option<String>,
+) -> Result<http_fetch::FetchResponse, AitError> {
+    let settings = load_or_default_settings(&app_handle);
+
+    let host = reqwest::Url::parse(&request.url)
+        .ok()
+        .and_then(|url| url.host_str().map(|h| h.to_string()))
+        .ok_or_else(|| AitError::Internal { message: format!("Invalid URL '{}'", request.url) })?;
+
+    if let Err(policy_error) = command_policy::check_host(&settings, &host) {
+        println!("Fetch blocked by policy: {}", policy_error.reason);
+        return Err(AitError::PermissionDenied {
+            message: format!("{} (pattern: {})", policy_error.reason, policy_error.pattern),
+        });
     }
-    
-    let search_response = response.json::<WebSearchResponse>()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-    
-    Ok(search_response)
+
+    if settings.auth_required {
+        let token = auth_token.as_deref().ok_or_else(|| AitError::PermissionDenied {
+            message: "This instance requires a signed authorization token to make outbound HTTP requests".to_string(),
+        })?;
+        auth::verify(token, &settings.auth_signing_secret, "fetch")?;
+    }
+
+    http_fetch::fetch(&app_handle, request).await
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+/// Maps an error variant to a stable, machine-readable identifier and an HTTP-ish
+/// status code, so callers across the Tauri boundary can branch on "missing key" vs
+/// "rate limited" vs "bad command" instead of string-matching a message.
+pub trait ErrorCode {
+    fn code(&self) -> &'static str;
+    fn status(&self) -> u16;
+}
+
+/// Typed error returned by commands that previously returned a bare `String`. Serializes
+/// as `{ "code": ..., "message": ... }` across the Tauri boundary.
+#[derive(Debug, Clone)]
+pub enum AitError {
+    PermissionDenied { message: String },
+    CommandNotFound { program: String },
+    ApiKeyMissing { provider: String },
+    UpstreamStatus { status: u16, message: String },
+    ParseFailure { message: String },
+    Timeout { message: String },
+    /// Catch-all for failures that don't fit a more specific variant yet.
+    Internal { message: String },
+}
+
+impl ErrorCode for AitError {
+    fn code(&self) -> &'static str {
+        match self {
+            AitError::PermissionDenied { .. } => "permission_denied",
+            AitError::CommandNotFound { .. } => "command_not_found",
+            AitError::ApiKeyMissing { .. } => "api_key_missing",
+            AitError::UpstreamStatus { .. } => "upstream_status",
+            AitError::ParseFailure { .. } => "parse_failure",
+            AitError::Timeout { .. } => "timeout",
+            AitError::Internal { .. } => "internal",
+        }
+    }
+
+    fn status(&self) -> u16 {
+        match self {
+            AitError::PermissionDenied { .. } => 403,
+            AitError::CommandNotFound { .. } => 404,
+            AitError::ApiKeyMissing { .. } => 401,
+            AitError::UpstreamStatus { status, .. } => *status,
+            AitError::ParseFailure { .. } => 502,
+            AitError::Timeout { .. } => 504,
+            AitError::Internal { .. } => 500,
+        }
+    }
+}
+
+impl AitError {
+    fn message(&self) -> String {
+        match self {
+            AitError::PermissionDenied { message } => message.clone(),
+            AitError::CommandNotFound { program } => {
+                format!("Program '{}' not found. Make sure it is installed and in your system PATH.", program)
+            }
+            AitError::ApiKeyMissing { provider } => format!("{} API key is not set", provider),
+            AitError::UpstreamStatus { status, message } => format!("Upstream request failed with status {}: {}", status, message),
+            AitError::ParseFailure { message } => message.clone(),
+            AitError::Timeout { message } => message.clone(),
+            AitError::Internal { message } => message.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for AitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.message())
+    }
+}
+
+/// Bridges the many internal helpers (persistence, cache) that still return a plain
+/// `String` error into the typed error surface.
+impl From<String> for AitError {
+    fn from(message: String) -> Self {
+        AitError::Internal { message }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorPayload {
+    code: String,
+    message: String,
+}
+
+impl Serialize for AitError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ErrorPayload { code: self.code().to_string(), message: self.message() }.serialize(serializer)
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use glob::Pattern;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::telemetry;
+
+/// Tauri event carrying scan progress (files scanned / bytes hashed so far).
+pub const SCAN_PROGRESS_EVENT: &str = "file_scan://progress";
+
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+static RUN_COUNTER: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+// Cooperative cancellation flags for in-flight scans, keyed by run id.
+static CANCEL_FLAGS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanOptions {
+    pub root: String,
+    #[serde(default)]
+    pub allowed_extensions: Vec<String>,
+    #[serde(default)]
+    pub excluded_extensions: Vec<String>,
+    #[serde(default)]
+    pub excluded_patterns: Vec<String>,
+    #[serde(default)]
+    pub run_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub group_id: usize,
+    pub size: u64,
+    pub hash: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanResult {
+    pub files: Vec<ScannedFile>,
+    pub duplicate_groups: Vec<DuplicateGroup>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScanProgressEvent {
+    run_id: String,
+    files_scanned: u64,
+    bytes_hashed: u64,
+}
+
+/// Generates a unique id for a scan run when the caller doesn't supply one.
+pub fn next_run_id() -> String {
+    let mut counter = RUN_COUNTER.lock().unwrap();
+    *counter += 1;
+    format!("scan-{}", counter)
+}
+
+/// Requests cooperative cancellation of the scan with the given id.
+pub fn cancel(run_id: &str) -> Result<(), String> {
+    let flags = CANCEL_FLAGS.lock().unwrap();
+    match flags.get(run_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        None => Err(format!("No running scan found for run id '{}'", run_id)),
+    }
+}
+
+/// Recursively scans `options.root`, applying the extension/pattern filters, then finds
+/// duplicate files by grouping candidates by exact size and hashing each size-group with
+/// two or more members. Symlinks and unreadable entries are skipped rather than failing
+/// the whole scan.
+pub fn scan(app_handle: AppHandle, options: ScanOptions) -> Result<ScanResult, String> {
+    let run_id = options.run_id.clone().unwrap_or_else(next_run_id);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    CANCEL_FLAGS.lock().unwrap().insert(run_id.clone(), stop_flag.clone());
+
+    let files = walk(Path::new(&options.root), &options, &stop_flag);
+
+    // Group by exact size; only size-groups with 2+ members are hash candidates.
+    let mut by_size: HashMap<u64, Vec<ScannedFile>> = HashMap::new();
+    for file in &files {
+        by_size.entry(file.size).or_default().push(file.clone());
+    }
+    let candidates: Vec<ScannedFile> = by_size
+        .into_values()
+        .filter(|group| group.len() >= 2)
+        .flatten()
+        .collect();
+
+    let files_scanned = files.len() as u64;
+    let bytes_hashed = Arc::new(AtomicU64::new(0));
+    let hashes: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+
+    let thread_count = telemetry::logical_cpu_count().max(1);
+    let chunk_size = (candidates.len() / thread_count).max(1);
+
+    std::thread::scope(|scope| {
+        for chunk in candidates.chunks(chunk_size) {
+            let stop_flag = stop_flag.clone();
+            let bytes_hashed = bytes_hashed.clone();
+            let app_handle = app_handle.clone();
+            let run_id = run_id.clone();
+            let hashes = &hashes;
+            scope.spawn(move || {
+                for file in chunk {
+                    if stop_flag.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Some(hash) = hash_file(Path::new(&file.path), &bytes_hashed) {
+                        hashes.lock().unwrap().insert(file.path.clone(), hash);
+                    }
+                    let _ = app_handle.emit(
+                        SCAN_PROGRESS_EVENT,
+                        ScanProgressEvent {
+                            run_id: run_id.clone(),
+                            files_scanned,
+                            bytes_hashed: bytes_hashed.load(Ordering::SeqCst),
+                        },
+                    );
+                }
+            });
+        }
+    });
+
+    CANCEL_FLAGS.lock().unwrap().remove(&run_id);
+    let cancelled = stop_flag.load(Ordering::SeqCst);
+
+    let hashes = hashes.into_inner().unwrap();
+    let mut groups: HashMap<(u64, String), Vec<String>> = HashMap::new();
+    for file in &candidates {
+        if let Some(hash) = hashes.get(&file.path) {
+            groups.entry((file.size, hash.clone())).or_default().push(file.path.clone());
+        }
+    }
+
+    let duplicate_groups = groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() >= 2)
+        .enumerate()
+        .map(|(group_id, ((size, hash), paths))| DuplicateGroup { group_id, size, hash, paths })
+        .collect();
+
+    Ok(ScanResult { files, duplicate_groups, cancelled })
+}
+
+fn walk(root: &Path, options: &ScanOptions, stop_flag: &Arc<AtomicBool>) -> Vec<ScannedFile> {
+    let mut results = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if stop_flag.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Skipping unreadable directory {:?}: {}", dir, e);
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            if stop_flag.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let path = entry.path();
+
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    eprintln!("Skipping unreadable entry {:?}: {}", path, e);
+                    continue;
+                }
+            };
+
+            // fs::DirEntry::metadata does not follow symlinks, so this is the symlink check.
+            if metadata.is_symlink() {
+                continue;
+            }
+
+            if is_excluded(&path, options) {
+                continue;
+            }
+
+            if metadata.is_dir() {
+                stack.push(path);
+            } else if metadata.is_file() && matches_extension_filters(&path, options) {
+                results.push(ScannedFile {
+                    path: path.to_string_lossy().to_string(),
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+
+    results
+}
+
+fn is_excluded(path: &Path, options: &ScanOptions) -> bool {
+    let path_str = path.to_string_lossy();
+    options.excluded_patterns.iter().any(|raw_pattern| {
+        Pattern::new(raw_pattern).map(|p| p.matches(&path_str)).unwrap_or(false)
+    })
+}
+
+fn matches_extension_filters(path: &Path, options: &ScanOptions) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+
+    if options.excluded_extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext)) {
+        return false;
+    }
+
+    if options.allowed_extensions.is_empty() {
+        return true;
+    }
+
+    options.allowed_extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+}
+
+/// Hashes `path` in buffered chunks with blake3, tracking the running byte count in
+/// `bytes_hashed` for progress reporting. Returns `None` if the file can't be read.
+fn hash_file(path: &Path, bytes_hashed: &Arc<AtomicU64>) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = [0u8; HASH_CHUNK_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes_hashed.fetch_add(read as u64, Ordering::SeqCst);
+    }
+
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options_with(allowed: &[&str], excluded: &[&str], patterns: &[&str]) -> ScanOptions {
+        ScanOptions {
+            root: ".".to_string(),
+            allowed_extensions: allowed.iter().map(|s| s.to_string()).collect(),
+            excluded_extensions: excluded.iter().map(|s| s.to_string()).collect(),
+            excluded_patterns: patterns.iter().map(|s| s.to_string()).collect(),
+            run_id: None,
+        }
+    }
+
+    #[test]
+    fn empty_allowed_list_admits_anything_not_excluded() {
+        let options = options_with(&[], &["log"], &[]);
+        assert!(matches_extension_filters(Path::new("notes.txt"), &options));
+        assert!(!matches_extension_filters(Path::new("debug.log"), &options));
+    }
+
+    #[test]
+    fn non_empty_allowed_list_rejects_unmatched_extensions() {
+        let options = options_with(&["rs", "toml"], &[], &[]);
+        assert!(matches_extension_filters(Path::new("main.rs"), &options));
+        assert!(matches_extension_filters(Path::new("Cargo.toml"), &options));
+        assert!(!matches_extension_filters(Path::new("README.md"), &options));
+    }
+
+    #[test]
+    fn excluded_extensions_win_over_allowed_list() {
+        let options = options_with(&["rs"], &["rs"], &[]);
+        assert!(!matches_extension_filters(Path::new("main.rs"), &options));
+    }
+
+    #[test]
+    fn extension_matching_ignores_case_and_leading_dot() {
+        let options = options_with(&[".RS"], &[], &[]);
+        assert!(matches_extension_filters(Path::new("main.rs"), &options));
+    }
+
+    #[test]
+    fn files_with_no_extension_only_match_an_empty_allowed_list() {
+        let options = options_with(&[], &[], &[]);
+        assert!(matches_extension_filters(Path::new("Makefile"), &options));
+
+        let options = options_with(&["rs"], &[], &[]);
+        assert!(!matches_extension_filters(Path::new("Makefile"), &options));
+    }
+
+    #[test]
+    fn is_excluded_matches_glob_patterns_against_the_full_path() {
+        let options = options_with(&[], &[], &["*/target/*"]);
+        assert!(is_excluded(Path::new("project/target/debug/main"), &options));
+        assert!(!is_excluded(Path::new("project/src/main.rs"), &options));
+    }
+
+    #[test]
+    fn is_excluded_ignores_invalid_glob_patterns_instead_of_excluding_everything() {
+        let options = options_with(&[], &[], &["["]);
+        assert!(!is_excluded(Path::new("anything"), &options));
+    }
+}
+
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+use std::time::Duration;
+
+use base64::Engine;
+use ed25519_dalek::pkcs8::DecodePrivateKey as Ed25519DecodePrivateKey;
+use ed25519_dalek::{Signer, SigningKey};
+use futures_util::StreamExt;
+use rsa::pkcs1v15::SigningKey as RsaSigningKey;
+use rsa::pkcs8::DecodePrivateKey as RsaDecodePrivateKey;
+use rsa::signature::{RandomizedSigner, SignatureEncoding};
+use rsa::RsaPrivateKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::error::AitError;
+use crate::key_store;
+
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+const DEFAULT_MAX_REDIRECTS: u32 = 5;
+const DEFAULT_MAX_RESPONSE_BYTES: u64 = 10 * 1024 * 1024;
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    Raw,
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureAlgorithm {
+    RsaSha256,
+    Ed25519,
+}
+
+/// Configures HTTP Message Signatures for a request. The private key is the first live
+/// credential-vault entry whose provider id equals `key_id` and that is scoped to the
+/// "sign" action (not the entry's own `key-N` id), rather than being passed in directly.
+/// `key_id` is also sent as the signature's `keyId`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SignatureConfig {
+    pub key_id: String,
+    pub algorithm: SignatureAlgorithm,
+}
+
+/// Parameters for a single outbound HTTP call. Mirrors the controls an agent needs to
+/// safely hit an arbitrary endpoint: method/body/headers, a redirect cap, a timeout, a
+/// response-size cap, and optional request signing.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FetchRequest {
+    pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub max_redirects: Option<u32>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub max_response_bytes: Option<u64>,
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+    #[serde(default)]
+    pub signature: Option<SignatureConfig>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body_text: Option<String>,
+    pub body_json: Option<serde_json::Value>,
+    pub body_base64: Option<String>,
+}
+
+/// Blocks requests to loopback, private, link-local (including the
+/// `169.254.169.254` cloud-metadata address), and other non-routable addresses, so
+/// `http_fetch` can't be used to reach the host's internal network or metadata
+/// endpoints regardless of what the caller-configured host allow/deny list permits.
+fn is_internal_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_internal_ipv4(v4),
+        IpAddr::V6(v6) => {
+            // An IPv4-mapped v6 address (`::ffff:a.b.c.d`) carries the real routing
+            // decision in its embedded v4 address, so check that instead of the v6
+            // bits — otherwise `::ffff:169.254.169.254` sails through as "just a v6
+            // address" even though it resolves to the metadata endpoint.
+            if let Some(mapped) = v6.to_ipv4_mapped() {
+                return is_internal_ipv4(&mapped);
+            }
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (v6.segments()[0] & 0xfe00) == 0xfc00 // unique local (fc00::/7)
+                || (v6.segments()[0] & 0xffc0) == 0xfe80 // link-local (fe80::/10)
+        }
+    }
+}
+
+fn is_internal_ipv4(v4: &Ipv4Addr) -> bool {
+    v4.is_loopback()
+        || v4.is_private()
+        || v4.is_link_local()
+        || v4.is_unspecified()
+        || v4.is_broadcast()
+        || v4.is_multicast()
+        || *v4 == Ipv4Addr::new(169, 254, 169, 254)
+}
+
+/// Rejects `host` if it's a literal internal address, or a hostname that resolves to
+/// one, so a DNS name can't be used to route around the address-based check.
+///
+/// Does the actual (blocking) DNS resolution. Async callers should run this on a
+/// blocking-pool thread via `reject_internal_host` instead of calling it directly.
+/// The exception is `reqwest::redirect::Policy::custom`'s callback below: it's
+/// already synchronous, so calling this inline there just blocks the calling thread
+/// briefly rather than risk re-entering the async runtime with `block_on`.
+fn reject_internal_host_blocking(host: &str) -> Result<(), AitError> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        if is_internal_address(&ip) {
+            return Err(AitError::PermissionDenied {
+                message: format!("Fetching internal/private address '{}' is not allowed", host),
+            });
+        }
+        return Ok(());
+    }
+
+    let resolved = (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| AitError::Internal { message: format!("Failed to resolve host '{}': {}", host, e) })?;
+
+    for addr in resolved {
+        if is_internal_address(&addr.ip()) {
+            return Err(AitError::PermissionDenied {
+                message: format!("Host '{}' resolves to a blocked internal/private address", host),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Async wrapper around `reject_internal_host_blocking` that runs the DNS resolution on
+/// a blocking-pool thread, the same way `execute_command`/`scan_directory` offload
+/// their own blocking work, so a slow/hanging resolver can't stall a tokio worker
+/// thread (and every other in-flight async command with it).
+async fn reject_internal_host(host: &str) -> Result<(), AitError> {
+    let host = host.to_string();
+    tauri::async_runtime::spawn_blocking(move || reject_internal_host_blocking(&host))
+        .await
+        .map_err(|e| AitError::Internal { message: format!("Host check task panicked: {}", e) })?
+}
+
+
+/// Runs a `FetchRequest`, optionally signing it with HTTP Message Signatures first, and
+/// decodes the response body according to `response_format`.
+pub async fn fetch(app_handle: &AppHandle, request: FetchRequest) -> Result<FetchResponse, AitError> {
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    let max_redirects = request.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS);
+    let max_response_bytes = request.max_response_bytes.unwrap_or(DEFAULT_MAX_RESPONSE_BYTES);
+
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|_| AitError::Internal { message: format!("Invalid HTTP method '{}'", request.method) })?;
+
+    let initial_host = reqwest::Url::parse(&request.url)
+        .ok()
+        .and_then(|url| url.host_str().map(|h| h.to_string()))
+        .ok_or_else(|| AitError::Internal { message: format!("Invalid URL '{}'", request.url) })?;
+    reject_internal_host(&initial_host).await?;
+
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= max_redirects as usize {
+                return attempt.error("too many redirects");
+            }
+            match attempt.url().host_str().map(reject_internal_host_blocking) {
+                Some(Ok(())) => attempt.follow(),
+                _ => attempt.stop(),
+            }
+        }))
+        .build()
+        .map_err(|e| AitError::Internal { message: format!("Failed to build HTTP client: {}", e) })?;
+
+    let body_bytes = request.body.clone().unwrap_or_default().into_bytes();
+
+    let mut builder = client.request(method, &request.url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+
+    if let Some(signature) = &request.signature {
+        for (name, value) in build_signature_headers(app_handle, &request.url, &body_bytes, signature)? {
+            builder = builder.header(name, value);
+        }
+    }
+
+    if request.body.is_some() {
+        builder = builder.body(body_bytes);
+    }
+
+    let response = builder.send().await.map_err(|e| {
+        if e.is_timeout() {
+            AitError::Timeout { message: format!("Request to '{}' timed out after {}ms", request.url, timeout.as_millis()) }
+        } else {
+            AitError::Internal { message: format!("Request failed: {}", e) }
+        }
+    })?;
+
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or_default().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    if response.content_length().is_some_and(|len| len > max_response_bytes) {
+        return Err(AitError::Internal {
+            message: format!("Response exceeds the {}-byte cap", max_response_bytes),
+        });
+    }
+
+    let raw_body = read_capped(response, max_response_bytes).await?;
+
+    let (body_text, body_json, body_base64) = match request.response_format {
+        ResponseFormat::Raw => (None, None, Some(base64::engine::general_purpose::STANDARD.encode(&raw_body))),
+        ResponseFormat::Text => (Some(String::from_utf8_lossy(&raw_body).to_string()), None, None),
+        ResponseFormat::Json => {
+            let value = serde_json::from_slice(&raw_body)
+                .map_err(|e| AitError::ParseFailure { message: format!("Failed to parse JSON response: {}", e) })?;
+            (None, Some(value), None)
+        }
+    };
+
+    Ok(FetchResponse { status, headers, body_text, body_json, body_base64 })
+}
+
+/// Reads the response body in chunks, aborting once `max_response_bytes` is exceeded
+/// rather than buffering an unbounded stream into memory.
+async fn read_capped(response: reqwest::Response, max_response_bytes: u64) -> Result<Vec<u8>, AitError> {
+    let mut stream = response.bytes_stream();
+    let mut buffer = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| AitError::Internal { message: format!("Failed to read response body: {}", e) })?;
+        buffer.extend_from_slice(&chunk);
+        if buffer.len() as u64 > max_response_bytes {
+            return Err(AitError::Internal {
+                message: format!("Response exceeds the {}-byte cap", max_response_bytes),
+            });
+        }
+    }
+    Ok(buffer)
+}
+
+/// Builds the `Host`/`Date`/`Digest`/`Signature` headers for HTTP Message Signatures,
+/// covering the request host, date, and body digest the same way the signature does.
+fn build_signature_headers(
+    app_handle: &AppHandle,
+    url: &str,
+    body: &[u8],
+    signature: &SignatureConfig,
+) -> Result<Vec<(String, String)>, AitError> {
+    let parsed_url = reqwest::Url::parse(url).map_err(|e| AitError::Internal { message: format!("Invalid URL: {}", e) })?;
+    let host = parsed_url
+        .host_str()
+        .ok_or_else(|| AitError::Internal { message: "URL has no host".to_string() })?
+        .to_string();
+    let date = httpdate::fmt_http_date(std::time::SystemTime::now());
+
+    let mut hasher = Sha256::new();
+    hasher.update(body);
+    let digest = format!("SHA-256={}", base64::engine::general_purpose::STANDARD.encode(hasher.finalize()));
+
+    let signing_string = format!("host: {}\ndate: {}\ndigest: {}", host, date, digest);
+
+    let private_key_pem = key_store::resolve(app_handle, &signature.key_id, "sign")?;
+    let signature_value = sign_with_key(&signing_string, &private_key_pem, signature.algorithm)?;
+
+    let signature_header = format!(
+        "keyId=\"{}\",algorithm=\"{}\",headers=\"host date digest\",signature=\"{}\"",
+        signature.key_id,
+        algorithm_name(signature.algorithm),
+        signature_value,
+    );
+
+    Ok(vec![
+        ("Host".to_string(), host),
+        ("Date".to_string(), date),
+        ("Digest".to_string(), digest),
+        ("Signature".to_string(), signature_header),
+    ])
+}
+
+fn algorithm_name(algorithm: SignatureAlgorithm) -> &'static str {
+    match algorithm {
+        SignatureAlgorithm::RsaSha256 => "rsa-sha256",
+        SignatureAlgorithm::Ed25519 => "ed25519",
+    }
+}
+
+fn sign_with_key(signing_string: &str, private_key_pem: &str, algorithm: SignatureAlgorithm) -> Result<String, AitError> {
+    match algorithm {
+        SignatureAlgorithm::RsaSha256 => {
+            let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+                .map_err(|e| AitError::Internal { message: format!("Invalid RSA private key: {}", e) })?;
+            let signing_key = RsaSigningKey::<Sha256>::new(private_key);
+            let signature = signing_key.sign_with_rng(&mut rand::thread_rng(), signing_string.as_bytes());
+            Ok(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+        }
+        SignatureAlgorithm::Ed25519 => {
+            let signing_key = SigningKey::from_pkcs8_pem(private_key_pem)
+                .map_err(|e| AitError::Internal { message: format!("Invalid Ed25519 private key: {}", e) })?;
+            let signature = signing_key.sign(signing_string.as_bytes());
+            Ok(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_plain_ipv4_internal_addresses() {
+        assert!(is_internal_address(&"127.0.0.1".parse().unwrap()));
+        assert!(is_internal_address(&"169.254.169.254".parse().unwrap()));
+        assert!(is_internal_address(&"10.0.0.5".parse().unwrap()));
+        assert!(!is_internal_address(&"93.184.216.34".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_ipv4_mapped_internal_addresses_in_a_v6_literal() {
+        assert!(is_internal_address(&"::ffff:169.254.169.254".parse().unwrap()));
+        assert!(is_internal_address(&"::ffff:127.0.0.1".parse().unwrap()));
+        assert!(is_internal_address(&"::ffff:10.0.0.5".parse().unwrap()));
+        assert!(!is_internal_address(&"::ffff:93.184.216.34".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_native_ipv6_internal_addresses() {
+        assert!(is_internal_address(&"::1".parse().unwrap()));
+        assert!(is_internal_address(&"fe80::1".parse().unwrap()));
+        assert!(is_internal_address(&"fc00::1".parse().unwrap()));
+        assert!(!is_internal_address(&"2606:2800:220:1:248:1893:25c8:1946".parse().unwrap()));
+    }
+}
@@ -0,0 +1,330 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::AitError;
+use crate::persistence;
+
+const KEYSTORE_FILE: &str = "keystore.json";
+const MASTER_KEY_FILE: &str = "keystore.master";
+
+// Guards the load_all -> mutate -> save_all critical section in create_key/revoke_key so
+// two concurrent writers can't both load the same snapshot and silently clobber each
+// other's change on save.
+static KEYSTORE_WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A secret sealed in the vault: the provider it authenticates (e.g. "brave", "openai"),
+/// the actions it's scoped to (e.g. "search", "exec"), and an optional expiry after which
+/// lookups treat it as gone even though the entry is still listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredKey {
+    id: String,
+    provider: String,
+    scopes: Vec<String>,
+    created_at: u64,
+    expires_at: Option<u64>,
+    revoked: bool,
+    // Base64 of (12-byte nonce || AES-256-GCM ciphertext).
+    sealed_secret: String,
+}
+
+/// Public view of a stored key: everything except the secret itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyHandle {
+    pub id: String,
+    pub provider: String,
+    pub scopes: Vec<String>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub revoked: bool,
+}
+
+impl From<&StoredKey> for KeyHandle {
+    fn from(key: &StoredKey) -> Self {
+        KeyHandle {
+            id: key.id.clone(),
+            provider: key.provider.clone(),
+            scopes: key.scopes.clone(),
+            created_at: key.created_at,
+            expires_at: key.expires_at,
+            revoked: key.revoked,
+        }
+    }
+}
+
+/// Derives the next id from the persisted keystore (highest existing `key-N` + 1) rather
+/// than an in-process counter, so a restart can't hand out an id that's already in use
+/// by a key written before the process started.
+fn next_key_id(existing: &[StoredKey]) -> String {
+    let next = existing
+        .iter()
+        .filter_map(|k| k.id.strip_prefix("key-").and_then(|n| n.parse::<u64>().ok()))
+        .max()
+        .unwrap_or(0)
+        + 1;
+    format!("key-{}", next)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AitError> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|_| AitError::Internal { message: "Failed to get app data directory".to_string() })?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AitError::Internal { message: format!("Failed to create app data directory: {}", e) })?;
+    Ok(dir)
+}
+
+/// Loads the master encryption key from disk, generating and persisting a fresh one on
+/// first use. Every secret in the vault is sealed with this key.
+///
+/// The key file sits in the same app-data directory as `keystore.json` and is
+/// `chmod 600`'d on Unix so only this user's processes can read it, but that's the
+/// extent of the protection: without OS-keychain integration (Keychain/DPAPI/Secret
+/// Service), this is obfuscation against a casual directory listing, not real
+/// at-rest protection against anyone who can read as this user (root, a backup of the
+/// app-data directory, malware running as the same account).
+fn load_or_create_master_key(app_handle: &AppHandle) -> Result<[u8; 32], AitError> {
+    let path = app_data_dir(app_handle)?.join(MASTER_KEY_FILE);
+
+    if let Ok(encoded) = std::fs::read_to_string(&path) {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| AitError::Internal { message: format!("Corrupt master key: {}", e) })?;
+        if bytes.len() == 32 {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&bytes);
+            return Ok(key);
+        }
+    }
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    std::fs::write(&path, base64::engine::general_purpose::STANDARD.encode(key))
+        .map_err(|e| AitError::Internal { message: format!("Failed to persist master key: {}", e) })?;
+    restrict_to_owner(&path)?;
+    Ok(key)
+}
+
+/// Restricts `path` to owner-only read/write (`0600`) on Unix, right after it's
+/// written, so the master key isn't left world/group-readable alongside the vault it
+/// protects. No-op on platforms without Unix permission bits.
+#[cfg(unix)]
+fn restrict_to_owner(path: &std::path::Path) -> Result<(), AitError> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| AitError::Internal { message: format!("Failed to restrict master key permissions: {}", e) })
+}
+
+#[cfg(not(unix))]
+fn restrict_to_owner(_path: &std::path::Path) -> Result<(), AitError> {
+    Ok(())
+}
+
+fn cipher_for(app_handle: &AppHandle) -> Result<Aes256Gcm, AitError> {
+    let key_bytes = load_or_create_master_key(app_handle)?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes)))
+}
+
+fn seal(cipher: &Aes256Gcm, secret: &str) -> Result<String, AitError> {
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), secret.as_bytes())
+        .map_err(|e| AitError::Internal { message: format!("Failed to seal secret: {}", e) })?;
+
+    let mut sealed = nonce_bytes.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+}
+
+fn unseal(cipher: &Aes256Gcm, sealed_secret: &str) -> Result<String, AitError> {
+    let raw = base64::engine::general_purpose::STANDARD
+        .decode(sealed_secret)
+        .map_err(|e| AitError::Internal { message: format!("Corrupt sealed secret: {}", e) })?;
+    if raw.len() < 12 {
+        return Err(AitError::Internal { message: "Corrupt sealed secret".to_string() });
+    }
+    let (nonce_bytes, ciphertext) = raw.split_at(12);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| AitError::Internal { message: format!("Failed to unseal secret: {}", e) })?;
+    String::from_utf8(plaintext).map_err(|e| AitError::Internal { message: format!("Corrupt secret: {}", e) })
+}
+
+// The keystore only ever needs its immediately-previous snapshot to recover from a
+// corrupt write — unlike settings/chats/folders, there's no value in keeping several
+// generations of numbered backups around for a file that's a few small JSON records.
+const KEYSTORE_BACKUP_MODE: persistence::BackupMode = persistence::BackupMode::Simple;
+
+fn load_all(app_handle: &AppHandle) -> Result<Vec<StoredKey>, AitError> {
+    let path = app_data_dir(app_handle)?.join(KEYSTORE_FILE);
+    Ok(persistence::read_with_recovery_mode(&path, KEYSTORE_BACKUP_MODE, |json| {
+        serde_json::from_str::<Vec<StoredKey>>(json).ok()
+    })
+    .unwrap_or_default())
+}
+
+fn save_all(app_handle: &AppHandle, keys: &[StoredKey]) -> Result<(), AitError> {
+    let path = app_data_dir(app_handle)?.join(KEYSTORE_FILE);
+    let json = serde_json::to_string_pretty(keys)
+        .map_err(|e| AitError::Internal { message: format!("Failed to serialize keystore: {}", e) })?;
+    persistence::write_atomic_with_backups(&path, &json, KEYSTORE_BACKUP_MODE).map_err(AitError::from)
+}
+
+/// Encrypts `secret` and adds it to the vault, scoped to `provider` and `scopes`, with an
+/// optional expiry (unix seconds). Returns a handle describing the key without the secret.
+pub fn create_key(
+    app_handle: &AppHandle,
+    provider: String,
+    secret: String,
+    scopes: Vec<String>,
+    expires_at: Option<u64>,
+) -> Result<KeyHandle, AitError> {
+    let cipher = cipher_for(app_handle)?;
+    let sealed_secret = seal(&cipher, &secret)?;
+
+    let _guard = KEYSTORE_WRITE_LOCK.lock().unwrap();
+    let mut keys = load_all(app_handle)?;
+    let stored = StoredKey {
+        id: next_key_id(&keys),
+        provider,
+        scopes,
+        created_at: now_secs(),
+        expires_at,
+        revoked: false,
+        sealed_secret,
+    };
+
+    let handle = KeyHandle::from(&stored);
+    keys.push(stored);
+    save_all(app_handle, &keys)?;
+    Ok(handle)
+}
+
+/// Lists every key's metadata. Never returns a secret.
+pub fn list_keys(app_handle: &AppHandle) -> Result<Vec<KeyHandle>, AitError> {
+    Ok(load_all(app_handle)?.iter().map(KeyHandle::from).collect())
+}
+
+/// Marks a key revoked. Revoked keys stay listed for audit but [`resolve`] skips them.
+pub fn revoke_key(app_handle: &AppHandle, key_id: &str) -> Result<(), AitError> {
+    let _guard = KEYSTORE_WRITE_LOCK.lock().unwrap();
+    let mut keys = load_all(app_handle)?;
+    let key = keys
+        .iter_mut()
+        .find(|k| k.id == key_id)
+        .ok_or_else(|| AitError::Internal { message: format!("No key found with id '{}'", key_id) })?;
+    key.revoked = true;
+    save_all(app_handle, &keys)
+}
+
+/// True if `key` is usable for `provider` scoped to `action` as of `now`: matching
+/// provider, not revoked, not expired, and scoped for the action.
+fn is_live_for(key: &StoredKey, provider: &str, action: &str, now: u64) -> bool {
+    key.provider == provider
+        && !key.revoked
+        && key.expires_at.map_or(true, |exp| exp > now)
+        && key.scopes.iter().any(|s| s == action)
+}
+
+/// Finds the first live (not revoked, not expired) key for `provider` scoped to `action`.
+fn select_live_key<'a>(keys: &'a [StoredKey], provider: &str, action: &str, now: u64) -> Option<&'a StoredKey> {
+    keys.iter().find(|k| is_live_for(k, provider, action, now))
+}
+
+/// Finds the first live (not revoked, not expired) key for `provider` scoped to `action`
+/// and decrypts its secret. Commands call this instead of accepting a raw `api_key`.
+pub fn resolve(app_handle: &AppHandle, provider: &str, action: &str) -> Result<String, AitError> {
+    let keys = load_all(app_handle)?;
+    let now = now_secs();
+
+    let stored = select_live_key(&keys, provider, action, now)
+        .ok_or_else(|| AitError::ApiKeyMissing { provider: provider.to_string() })?;
+
+    let cipher = cipher_for(app_handle)?;
+    unseal(&cipher, &stored.sealed_secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(id: &str, provider: &str, scopes: &[&str], revoked: bool, expires_at: Option<u64>) -> StoredKey {
+        StoredKey {
+            id: id.to_string(),
+            provider: provider.to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            created_at: 0,
+            expires_at,
+            revoked,
+            sealed_secret: String::new(),
+        }
+    }
+
+    #[test]
+    fn next_key_id_continues_past_the_highest_existing_id() {
+        let existing = vec![stored("key-1", "brave", &["search"], false, None), stored("key-7", "openai", &["search"], false, None)];
+        assert_eq!(next_key_id(&existing), "key-8");
+    }
+
+    #[test]
+    fn next_key_id_starts_at_one_when_empty() {
+        assert_eq!(next_key_id(&[]), "key-1");
+    }
+
+    #[test]
+    fn next_key_id_ignores_ids_not_shaped_like_key_n() {
+        let existing = vec![stored("legacy-id", "brave", &["search"], false, None)];
+        assert_eq!(next_key_id(&existing), "key-1");
+    }
+
+    #[test]
+    fn select_live_key_skips_revoked_and_expired_and_mismatched_scope() {
+        let keys = vec![
+            stored("key-1", "brave", &["search"], true, None),
+            stored("key-2", "brave", &["search"], false, Some(1)),
+            stored("key-3", "brave", &["sign"], false, None),
+            stored("key-4", "brave", &["search"], false, None),
+        ];
+        let found = select_live_key(&keys, "brave", "search", 1000).unwrap();
+        assert_eq!(found.id, "key-4");
+    }
+
+    #[test]
+    fn select_live_key_treats_no_expiry_as_never_expiring() {
+        let keys = vec![stored("key-1", "brave", &["search"], false, None)];
+        assert!(select_live_key(&keys, "brave", "search", u64::MAX).is_some());
+    }
+
+    #[test]
+    fn seal_and_unseal_round_trip() {
+        let key_bytes = [7u8; 32];
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+        let sealed = seal(&cipher, "top secret").unwrap();
+        assert_ne!(sealed, "top secret");
+        assert_eq!(unseal(&cipher, &sealed).unwrap(), "top secret");
+    }
+
+    #[test]
+    fn unseal_rejects_corrupt_sealed_secret() {
+        let key_bytes = [7u8; 32];
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+        assert!(unseal(&cipher, "not-base64!!").is_err());
+    }
+}
@@ -3,6 +3,18 @@ use tauri::Builder;
 
 // Import the commands module
 mod commands;
+mod auth;
+mod command_policy;
+mod error;
+mod fs_scan;
+mod http_fetch;
+mod key_store;
+mod persistence;
+mod process_runner;
+mod search_cache;
+mod search_provider;
+mod search_query;
+mod telemetry;
 
 // Define API provider enum
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -89,10 +101,22 @@ pub fn run() {
             crate::commands::get_cwd,
             crate::commands::get_os_info,
             crate::commands::get_memory_info,
+            crate::commands::get_telemetry_snapshot,
+            crate::commands::start_telemetry_sampler,
+            crate::commands::stop_telemetry_sampler,
             crate::commands::get_settings,
             crate::commands::save_settings,
             crate::commands::execute_command,
+            crate::commands::cancel_command,
+            crate::commands::create_key,
+            crate::commands::list_keys,
+            crate::commands::revoke_key,
             crate::commands::web_search,
+            crate::commands::force_refresh_search,
+            crate::commands::clear_expired_search_cache,
+            crate::commands::http_fetch,
+            crate::commands::scan_directory,
+            crate::commands::cancel_file_scan,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many (if any) previous copies of a file are kept before it is overwritten.
+#[derive(Debug, Clone, Copy)]
+pub enum BackupMode {
+    /// Keep up to `count` rotating numbered backups: `path.~1~`, `path.~2~`, ...
+    /// oldest discarded once the count is exceeded.
+    Numbered(usize),
+    /// Keep exactly one backup at `path.bak`, overwritten on every save. Used for
+    /// files like the keystore where a single most-recent snapshot is enough and the
+    /// numbered-rotation churn isn't worth it.
+    Simple,
+}
+
+const DEFAULT_BACKUP_MODE: BackupMode = BackupMode::Numbered(3);
+
+/// Writes `contents` to `path` atomically using the default backup mode: the data is
+/// written to a temporary file in the same directory and then renamed into place, so a
+/// crash or failed serialization mid-write can never leave `path` truncated.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    write_atomic_with_backups(path, contents, DEFAULT_BACKUP_MODE)
+}
+
+/// Same as [`write_atomic`] but with an explicit [`BackupMode`].
+pub fn write_atomic_with_backups(path: &Path, contents: &str, mode: BackupMode) -> Result<(), String> {
+    rotate_backups(path, mode)?;
+
+    let tmp_path = tmp_path_for(path);
+    fs::write(&tmp_path, contents)
+        .map_err(|e| format!("Failed to write temp file {:?}: {}", tmp_path, e))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to rename temp file into place at {:?}: {}", path, e))?;
+    Ok(())
+}
+
+/// Reads `path` and parses it with `parse`. If the primary file is missing, unreadable,
+/// or fails to parse, falls back to the most recent backup that parses successfully
+/// (using the default backup mode). Returns `None` if nothing usable was found.
+pub fn read_with_recovery<T>(path: &Path, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    read_with_recovery_mode(path, DEFAULT_BACKUP_MODE, parse)
+}
+
+/// Same as [`read_with_recovery`] but with an explicit [`BackupMode`].
+pub fn read_with_recovery_mode<T>(
+    path: &Path,
+    mode: BackupMode,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    if let Ok(contents) = fs::read_to_string(path) {
+        if let Some(parsed) = parse(&contents) {
+            return Some(parsed);
+        }
+        eprintln!("Primary file {:?} failed to parse, attempting backup recovery", path);
+    }
+
+    for backup in backup_paths(path, mode) {
+        if let Ok(contents) = fs::read_to_string(&backup) {
+            if let Some(parsed) = parse(&contents) {
+                println!("Recovered data from backup {:?}", backup);
+                return Some(parsed);
+            }
+        }
+    }
+
+    None
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+fn numbered_backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".~{}~", n));
+    PathBuf::from(backup)
+}
+
+fn simple_backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+/// Backup paths to check on recovery, most recent first.
+fn backup_paths(path: &Path, mode: BackupMode) -> Vec<PathBuf> {
+    match mode {
+        BackupMode::Numbered(count) => (1..=count).map(|n| numbered_backup_path(path, n)).collect(),
+        BackupMode::Simple => vec![simple_backup_path(path)],
+    }
+}
+
+/// Rotates existing numbered backups up by one slot and copies the current contents of
+/// `path` (if it exists) into the first slot, or for [`BackupMode::Simple`] just copies
+/// `path` over the single `.bak` file.
+fn rotate_backups(path: &Path, mode: BackupMode) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    match mode {
+        BackupMode::Numbered(count) => {
+            if count == 0 {
+                return Ok(());
+            }
+
+            for n in (1..count).rev() {
+                let from = numbered_backup_path(path, n);
+                let to = numbered_backup_path(path, n + 1);
+                if from.exists() {
+                    fs::rename(&from, &to)
+                        .map_err(|e| format!("Failed to rotate backup {:?}: {}", from, e))?;
+                }
+            }
+
+            let first_backup = numbered_backup_path(path, 1);
+            fs::copy(path, &first_backup)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to create backup {:?}: {}", first_backup, e))
+        }
+        BackupMode::Simple => {
+            let backup = simple_backup_path(path);
+            fs::copy(path, &backup)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to create backup {:?}: {}", backup, e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    /// A path under the OS temp dir, unique per test run, cleaned up by the caller.
+    fn scratch_path(name: &str) -> PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("ait_persistence_test_{}_{}_{}", std::process::id(), n, name))
+    }
+
+    fn parse_str(json: &str) -> Option<String> {
+        Some(json.to_string())
+    }
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let path = scratch_path("roundtrip.json");
+        write_atomic(&path, "hello").unwrap();
+        assert_eq!(read_with_recovery(&path, parse_str), Some("hello".to_string()));
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn read_recovers_from_backup_when_primary_is_corrupt() {
+        let path = scratch_path("recover.json");
+        write_atomic(&path, "{\"valid\":true}").unwrap();
+        // Second write rotates the first write into the `.~1~` backup slot.
+        write_atomic(&path, "{\"valid\":true}").unwrap();
+        // Corrupt the primary file directly, bypassing write_atomic's rotation.
+        fs::write(&path, "not valid json").unwrap();
+
+        let recovered = read_with_recovery(&path, |json| serde_json::from_str::<serde_json::Value>(json).ok());
+        assert!(recovered.is_some());
+
+        fs::remove_file(&path).ok();
+        fs::remove_file(numbered_backup_path(&path, 1)).ok();
+    }
+
+    #[test]
+    fn read_returns_none_when_nothing_is_recoverable() {
+        let path = scratch_path("missing.json");
+        assert!(read_with_recovery(&path, parse_str).is_none());
+    }
+
+    #[test]
+    fn numbered_backups_rotate_oldest_out() {
+        let path = scratch_path("rotate.json");
+        let mode = BackupMode::Numbered(2);
+
+        write_atomic_with_backups(&path, "v1", mode).unwrap();
+        write_atomic_with_backups(&path, "v2", mode).unwrap();
+        write_atomic_with_backups(&path, "v3", mode).unwrap();
+
+        // After three writes with a 2-slot rotation, the backups hold v2 (slot 1) and
+        // v1 (slot 2); v3 is the live file and there is no slot 3.
+        assert_eq!(fs::read_to_string(numbered_backup_path(&path, 1)).unwrap(), "v2");
+        assert_eq!(fs::read_to_string(numbered_backup_path(&path, 2)).unwrap(), "v1");
+        assert!(!numbered_backup_path(&path, 3).exists());
+
+        fs::remove_file(&path).ok();
+        fs::remove_file(numbered_backup_path(&path, 1)).ok();
+        fs::remove_file(numbered_backup_path(&path, 2)).ok();
+    }
+
+    #[test]
+    fn simple_mode_keeps_single_overwritten_backup() {
+        let path = scratch_path("simple.json");
+        let mode = BackupMode::Simple;
+
+        write_atomic_with_backups(&path, "{\"v\":1}", mode).unwrap();
+        write_atomic_with_backups(&path, "{\"v\":2}", mode).unwrap();
+        write_atomic_with_backups(&path, "{\"v\":3}", mode).unwrap();
+
+        // Only one `.bak` slot exists and it always holds the previous write, not the
+        // full numbered history.
+        assert_eq!(fs::read_to_string(simple_backup_path(&path)).unwrap(), "{\"v\":2}");
+        assert!(!numbered_backup_path(&path, 1).exists());
+
+        // Corrupting the primary still recovers from that single backup.
+        fs::write(&path, "not valid json").unwrap();
+        let recovered =
+            read_with_recovery_mode(&path, mode, |json| serde_json::from_str::<serde_json::Value>(json).ok());
+        assert_eq!(recovered, Some(serde_json::json!({"v": 2})));
+
+        fs::remove_file(&path).ok();
+        fs::remove_file(simple_backup_path(&path)).ok();
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AitError;
+
+/// Tauri event carrying one line of stdout/stderr from a streaming command run.
+pub const COMMAND_OUTPUT_EVENT: &str = "command://output";
+
+static RUN_COUNTER: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+// Cooperative stop flags for in-flight runs, keyed by run id.
+static STOP_FLAGS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutputEvent {
+    pub run_id: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub line: String,
+}
+
+/// Generates a unique id for a command run when the caller doesn't supply one.
+pub fn next_run_id() -> String {
+    let mut counter = RUN_COUNTER.lock().unwrap();
+    *counter += 1;
+    format!("run-{}", counter)
+}
+
+/// Requests cooperative cancellation of the run with the given id. The running command
+/// polls its stop flag and kills the child process on its next check.
+pub fn cancel(run_id: &str) -> Result<(), String> {
+    let flags = STOP_FLAGS.lock().unwrap();
+    match flags.get(run_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        None => Err(format!("No running command found for run id '{}'", run_id)),
+    }
+}
+
+/// Spawns `command`, streaming each line of stdout/stderr to the frontend via
+/// `COMMAND_OUTPUT_EVENT` as it is produced, and resolves with the full captured output
+/// once the process exits (or is killed after a [`cancel`] call).
+pub fn run(app_handle: AppHandle, run_id: String, command: &str, cwd: &str) -> Result<String, AitError> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().ok_or_else(|| AitError::Internal {
+        message: "Empty command".to_string(),
+    })?;
+    let args: Vec<&str> = parts.collect();
+
+    println!("Executing command (run {}): '{}' in directory: '{}'", run_id, command, cwd);
+
+    let mut cmd = Command::new(program);
+    cmd.args(&args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    if Path::new(cwd).exists() {
+        cmd.current_dir(cwd);
+        println!("Working directory set to: '{}'", cwd);
+    } else {
+        println!("Warning: Directory '{}' does not exist, using current directory", cwd);
+    }
+
+    let mut child = cmd.spawn().map_err(|e| map_spawn_error(&e, program))?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    let stdout_buf = Arc::new(Mutex::new(String::new()));
+    let stderr_buf = Arc::new(Mutex::new(String::new()));
+
+    let stdout_thread = spawn_line_reader(app_handle.clone(), run_id.clone(), "stdout", stdout, stdout_buf.clone());
+    let stderr_thread = spawn_line_reader(app_handle.clone(), run_id.clone(), "stderr", stderr, stderr_buf.clone());
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    STOP_FLAGS.lock().unwrap().insert(run_id.clone(), stop_flag.clone());
+
+    let wait_result = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {
+                if stop_flag.load(Ordering::SeqCst) {
+                    println!("Run '{}' cancelled, killing process", run_id);
+                    let _ = child.kill();
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => break Err(e),
+        }
+    };
+
+    STOP_FLAGS.lock().unwrap().remove(&run_id);
+    let _ = stdout_thread.join();
+    let _ = stderr_thread.join();
+
+    let status = wait_result.map_err(|e| AitError::Internal {
+        message: format!("Failed to wait on process: {}", e),
+    })?;
+
+    let stdout = stdout_buf.lock().unwrap().clone();
+    let stderr = stderr_buf.lock().unwrap().clone();
+
+    println!("Command execution status: {}", status);
+    println!("stdout length: {}, stderr length: {}", stdout.len(), stderr.len());
+
+    if !stderr.is_empty() {
+        if status.success() {
+            Ok(format!("{}\n\nWarnings:\n{}", stdout, stderr))
+        } else {
+            Err(AitError::Internal {
+                message: format!("Command failed with error:\n{}", stderr),
+            })
+        }
+    } else if stdout.is_empty() && !status.success() {
+        Err(AitError::Internal {
+            message: format!("Command failed with no output. Exit code: {}", status),
+        })
+    } else {
+        Ok(stdout)
+    }
+}
+
+fn map_spawn_error(e: &std::io::Error, program: &str) -> AitError {
+    println!("Command execution error: {}", e);
+    let error_msg = e.to_string();
+    if error_msg.contains("not found") || error_msg.contains("No such file or directory") {
+        AitError::CommandNotFound { program: program.to_string() }
+    } else if error_msg.contains("permission denied") {
+        AitError::PermissionDenied {
+            message: format!("Permission denied executing '{}'", program),
+        }
+    } else {
+        AitError::Internal { message: format!("Failed to execute command: {}", e) }
+    }
+}
+
+fn spawn_line_reader<R: Read + Send + 'static>(
+    app_handle: AppHandle,
+    run_id: String,
+    stream: &'static str,
+    reader: R,
+    buffer: Arc<Mutex<String>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    buffer.lock().unwrap().push_str(&line);
+                    let _ = app_handle.emit(
+                        COMMAND_OUTPUT_EVENT,
+                        CommandOutputEvent {
+                            run_id: run_id.clone(),
+                            stream: stream.to_string(),
+                            line: line.trim_end_matches('\n').to_string(),
+                        },
+                    );
+                }
+                Err(_) => break,
+            }
+        }
+    })
+}
@@ -0,0 +1,179 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::WebSearchResponse;
+use crate::persistence;
+use crate::search_query::SearchQuery;
+
+/// Default max-age before a cached search result is considered stale, in seconds.
+pub const DEFAULT_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedSearch {
+    fetched_at: u64, // seconds since the Unix epoch
+    response: WebSearchResponse,
+}
+
+/// A cache hit, along with how old it is so the caller can decide what to do with it.
+pub struct CacheHit {
+    pub response: WebSearchResponse,
+    pub age_secs: u64,
+}
+
+fn cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|_| "Failed to get app data directory".to_string())?;
+    let dir = data_dir.join("search_cache");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create search cache directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Normalizes the full query (not just `q`) into a stable cache key, so searches that
+/// differ in page, freshness, safesearch, etc. don't collide on the same entry even
+/// when the search text is identical.
+fn cache_key(query: &SearchQuery) -> String {
+    let normalized_q = query.q.trim().to_lowercase();
+    let mut result_types = query.result_types.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>();
+    result_types.sort();
+
+    let key_input = format!(
+        "q={}|offset={}|count={}|country={:?}|lang={:?}|freshness={:?}|safesearch={:?}|types={:?}|highlight={}|crop={:?}",
+        normalized_q,
+        query.offset,
+        query.count,
+        query.country.as_deref().map(|s| s.to_lowercase()),
+        query.search_lang.as_deref().map(|s| s.to_lowercase()),
+        query.freshness,
+        query.safesearch,
+        result_types,
+        query.highlight,
+        query.crop_length,
+    );
+
+    let mut hasher = DefaultHasher::new();
+    key_input.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn cache_path(app_handle: &AppHandle, query: &SearchQuery) -> Result<PathBuf, String> {
+    Ok(cache_dir(app_handle)?.join(format!("{}.json", cache_key(query))))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Looks up a cached response for `query` regardless of age. Returns `None` if there is
+/// no entry or it cannot be parsed.
+fn lookup(app_handle: &AppHandle, query: &SearchQuery) -> Option<CacheHit> {
+    let path = cache_path(app_handle, query).ok()?;
+    let json = fs::read_to_string(&path).ok()?;
+    let cached: CachedSearch = serde_json::from_str(&json).ok()?;
+    let age_secs = now_secs().saturating_sub(cached.fetched_at);
+    Some(CacheHit { response: cached.response, age_secs })
+}
+
+/// Whether a cache entry of `age_secs` should be served: always when `offline` (a
+/// stale entry beats no entry), otherwise only while younger than `max_age_secs`.
+fn is_fresh(age_secs: u64, max_age_secs: u64, offline: bool) -> bool {
+    offline || age_secs < max_age_secs
+}
+
+/// Returns a cached response for `query` if it is younger than `max_age_secs`, or if
+/// `offline` is set (in which case a stale entry is returned rather than nothing).
+pub fn get_fresh(app_handle: &AppHandle, query: &SearchQuery, max_age_secs: u64, offline: bool) -> Option<WebSearchResponse> {
+    let hit = lookup(app_handle, query)?;
+    if is_fresh(hit.age_secs, max_age_secs, offline) {
+        Some(hit.response)
+    } else {
+        None
+    }
+}
+
+/// Stores `response` for `query`, overwriting any existing entry.
+pub fn store(app_handle: &AppHandle, query: &SearchQuery, response: &WebSearchResponse) -> Result<(), String> {
+    let path = cache_path(app_handle, query)?;
+    let cached = CachedSearch { fetched_at: now_secs(), response: response.clone() };
+    let json = serde_json::to_string_pretty(&cached)
+        .map_err(|e| format!("Failed to serialize cached search result: {}", e))?;
+    persistence::write_atomic(&path, &json)
+}
+
+/// Deletes every cache entry older than `max_age_secs`. Returns the number removed.
+pub fn clear_expired(app_handle: &AppHandle, max_age_secs: u64) -> Result<usize, String> {
+    let dir = cache_dir(app_handle)?;
+    let mut removed = 0;
+
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read search cache directory: {}", e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let is_expired = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<CachedSearch>(&json).ok())
+            .map(|cached| !is_fresh(now_secs().saturating_sub(cached.fetched_at), max_age_secs, false))
+            .unwrap_or(false);
+
+        if is_expired {
+            if fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_query::SearchQuery;
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery::simple(q, 5)
+    }
+
+    #[test]
+    fn is_fresh_allows_entries_younger_than_max_age() {
+        assert!(is_fresh(10, 60, false));
+        assert!(!is_fresh(60, 60, false));
+        assert!(!is_fresh(61, 60, false));
+    }
+
+    #[test]
+    fn is_fresh_accepts_any_age_when_offline() {
+        assert!(is_fresh(0, 60, true));
+        assert!(is_fresh(1_000_000, 60, true));
+    }
+
+    #[test]
+    fn cache_key_is_stable_for_the_same_query() {
+        assert_eq!(cache_key(&query("rust async runtimes")), cache_key(&query("rust async runtimes")));
+    }
+
+    #[test]
+    fn cache_key_ignores_case_and_surrounding_whitespace_in_q() {
+        assert_eq!(cache_key(&query("  Rust Async  ")), cache_key(&query("rust async")));
+    }
+
+    #[test]
+    fn cache_key_differs_when_non_q_fields_differ() {
+        let mut with_offset = query("rust");
+        with_offset.offset = 10;
+        assert_ne!(cache_key(&query("rust")), cache_key(&with_offset));
+    }
+}
@@ -0,0 +1,396 @@
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use scraper::{Html, Selector};
+
+use crate::commands::{WebSearchResponse, WebSearchResult, WebSearchWeb};
+use crate::error::AitError;
+use crate::search_query::{ResultType, SearchQuery};
+
+/// A source of web search results, normalized into the existing `WebSearchResponse`
+/// shape regardless of whether it talks to a JSON API or scrapes a results page.
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    async fn search(&self, query: &SearchQuery) -> Result<WebSearchResponse, AitError>;
+}
+
+/// Brave's JSON search API, the original (and still default) provider.
+pub struct BraveProvider {
+    pub api_key: String,
+}
+
+#[async_trait]
+impl SearchProvider for BraveProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<WebSearchResponse, AitError> {
+        if self.api_key.is_empty() {
+            return Err(AitError::ApiKeyMissing { provider: "Brave Search".to_string() });
+        }
+
+        let mut url = format!(
+            "https://api.search.brave.com/res/v1/web/search?q={}&count={}&offset={}&safesearch={}",
+            urlencoding::encode(&query.q),
+            query.count,
+            query.offset,
+            safesearch_param(query),
+        );
+
+        if let Some(country) = &query.country {
+            url.push_str(&format!("&country={}", urlencoding::encode(country)));
+        }
+        if let Some(search_lang) = &query.search_lang {
+            url.push_str(&format!("&search_lang={}", urlencoding::encode(search_lang)));
+        }
+        if let Some(freshness) = &query.freshness {
+            url.push_str(&format!("&freshness={}", freshness.as_brave_code()));
+        }
+        if query.result_types.iter().any(|t| *t != ResultType::Web) {
+            url.push_str(&format!("&result_filter={}", result_types_param(query)));
+        }
+
+        let client = reqwest::Client::new();
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+                .map_err(|e| AitError::Internal { message: format!("Invalid API key: {}", e) })?,
+        );
+
+        let response = client
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(|e| AitError::Internal { message: format!("Failed to send request: {}", e) })?;
+
+        if !response.status().is_success() {
+            return Err(AitError::UpstreamStatus {
+                status: response.status().as_u16(),
+                message: "Brave Search API request failed".to_string(),
+            });
+        }
+
+        let mut parsed = response
+            .json::<WebSearchResponse>()
+            .await
+            .map_err(|e| AitError::ParseFailure { message: format!("Failed to parse response: {}", e) })?;
+        parsed.offset = query.offset;
+        Ok(parsed)
+    }
+}
+
+fn safesearch_param(query: &SearchQuery) -> &'static str {
+    use crate::search_query::SafeSearch;
+    match query.safesearch {
+        SafeSearch::Off => "off",
+        SafeSearch::Moderate => "moderate",
+        SafeSearch::Strict => "strict",
+    }
+}
+
+fn result_types_param(query: &SearchQuery) -> String {
+    query
+        .result_types
+        .iter()
+        .map(|t| match t {
+            ResultType::Web => "web",
+            ResultType::News => "news",
+            ResultType::Images => "images",
+            ResultType::Videos => "videos",
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Scrapes a provider's normal results HTML page instead of calling its JSON API. Used
+/// both as a standalone provider (e.g. DuckDuckGo, which has no public JSON endpoint)
+/// and as the fallback when a JSON provider errors or silently drops results the
+/// web frontend still shows.
+pub struct ScrapeHtmlProvider {
+    /// `{query}` is replaced with the URL-encoded query string.
+    pub results_url_template: String,
+    pub result_selector: String,
+    pub title_selector: String,
+    pub link_selector: String,
+    pub snippet_selector: String,
+}
+
+impl ScrapeHtmlProvider {
+    /// DuckDuckGo's HTML-only results page, which works without an API key.
+    pub fn duckduckgo() -> Self {
+        Self {
+            results_url_template: "https://duckduckgo.com/html/?q={query}".to_string(),
+            result_selector: "div.result".to_string(),
+            title_selector: "a.result__a".to_string(),
+            link_selector: "a.result__a".to_string(),
+            snippet_selector: "a.result__snippet".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ScrapeHtmlProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<WebSearchResponse, AitError> {
+        let url = self
+            .results_url_template
+            .replace("{query}", &urlencoding::encode(&query.q));
+
+        let client = reqwest::Client::new();
+        let body = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AitError::Internal { message: format!("Failed to fetch results page: {}", e) })?
+            .text()
+            .await
+            .map_err(|e| AitError::Internal { message: format!("Failed to read results page body: {}", e) })?;
+
+        let document = Html::parse_document(&body);
+        let result_selector = Selector::parse(&self.result_selector)
+            .map_err(|e| AitError::ParseFailure { message: format!("Invalid result selector: {:?}", e) })?;
+        let title_selector = Selector::parse(&self.title_selector)
+            .map_err(|e| AitError::ParseFailure { message: format!("Invalid title selector: {:?}", e) })?;
+        let link_selector = Selector::parse(&self.link_selector)
+            .map_err(|e| AitError::ParseFailure { message: format!("Invalid link selector: {:?}", e) })?;
+        let snippet_selector = Selector::parse(&self.snippet_selector)
+            .map_err(|e| AitError::ParseFailure { message: format!("Invalid snippet selector: {:?}", e) })?;
+
+        let mut results = Vec::new();
+        for element in document.select(&result_selector).take(query.count as usize) {
+            let title = element
+                .select(&title_selector)
+                .next()
+                .map(|e| e.text().collect::<String>())
+                .unwrap_or_default();
+            let url = element
+                .select(&link_selector)
+                .next()
+                .and_then(|e| e.value().attr("href"))
+                .unwrap_or_default()
+                .to_string();
+            let description = element
+                .select(&snippet_selector)
+                .next()
+                .map(|e| e.text().collect::<String>())
+                .unwrap_or_default();
+
+            if !title.is_empty() && !url.is_empty() {
+                results.push(WebSearchResult { title, url, description });
+            }
+        }
+
+        Ok(WebSearchResponse { web: WebSearchWeb { results }, offset: query.offset })
+    }
+}
+
+/// A self-hosted SearXNG instance's JSON API (`?format=json`).
+pub struct SearXNGProvider {
+    pub instance_url: String,
+}
+
+#[async_trait]
+impl SearchProvider for SearXNGProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<WebSearchResponse, AitError> {
+        let url = format!(
+            "{}/search?q={}&format=json&pageno={}",
+            self.instance_url.trim_end_matches('/'),
+            urlencoding::encode(&query.q),
+            query.offset / query.count.max(1) + 1,
+        );
+
+        #[derive(serde::Deserialize)]
+        struct SearXNGResult {
+            title: String,
+            url: String,
+            #[serde(default)]
+            content: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct SearXNGResponse {
+            results: Vec<SearXNGResult>,
+        }
+
+        let response = reqwest::get(&url)
+            .await
+            .map_err(|e| AitError::Internal { message: format!("Failed to send request: {}", e) })?;
+
+        if !response.status().is_success() {
+            return Err(AitError::UpstreamStatus {
+                status: response.status().as_u16(),
+                message: "SearXNG instance request failed".to_string(),
+            });
+        }
+
+        let parsed = response
+            .json::<SearXNGResponse>()
+            .await
+            .map_err(|e| AitError::ParseFailure { message: format!("Failed to parse response: {}", e) })?;
+
+        let results = parsed
+            .results
+            .into_iter()
+            .take(query.count as usize)
+            .map(|r| WebSearchResult { title: r.title, url: r.url, description: r.content })
+            .collect();
+
+        Ok(WebSearchResponse { web: WebSearchWeb { results }, offset: query.offset })
+    }
+}
+
+/// Builds a provider by name, falling back to the DuckDuckGo scraper for anything
+/// unrecognized so `web_search` never hard-fails on a typo'd provider id.
+pub fn provider_for(name: &str, api_key: &str, searxng_instance_url: &str) -> Box<dyn SearchProvider> {
+    match name {
+        "brave" => Box::new(BraveProvider { api_key: api_key.to_string() }),
+        "searxng" => Box::new(SearXNGProvider { instance_url: searxng_instance_url.to_string() }),
+        "duckduckgo" | "scrape" => Box::new(ScrapeHtmlProvider::duckduckgo()),
+        _ => {
+            eprintln!("Unknown search provider '{}', falling back to DuckDuckGo scraping", name);
+            Box::new(ScrapeHtmlProvider::duckduckgo())
+        }
+    }
+}
+
+/// Runs `primary`, falling back to `fallback` when the primary provider errors or
+/// returns zero results, mirroring how some clients scrape the results page because the
+/// official API silently drops or 404s on entries the web frontend still shows. Applies
+/// highlighting/cropping to whichever response is returned.
+pub async fn search_with_fallback(
+    primary: &dyn SearchProvider,
+    fallback: &dyn SearchProvider,
+    query: &SearchQuery,
+) -> Result<WebSearchResponse, AitError> {
+    let mut response = match primary.search(query).await {
+        Ok(response) if !response.web.results.is_empty() => response,
+        Ok(_) => {
+            println!("Primary search returned zero results for '{}', falling back", query.q);
+            fallback.search(query).await?
+        }
+        Err(e) => {
+            println!("Primary search failed ({}), falling back for '{}'", e, query.q);
+            fallback.search(query).await?
+        }
+    };
+
+    apply_highlight_and_crop(&mut response, query);
+    Ok(response)
+}
+
+/// Truncates each result's description to `crop_length` (if set) and, when `highlight`
+/// is on, wraps occurrences of the query terms in `**markers**` so the caller can render
+/// matched terms without re-running the search-term matching itself.
+fn apply_highlight_and_crop(response: &mut WebSearchResponse, query: &SearchQuery) {
+    let terms: Vec<String> = query.q.split_whitespace().map(|t| t.to_lowercase()).collect();
+
+    for result in &mut response.web.results {
+        if let Some(crop_length) = query.crop_length {
+            if result.description.len() > crop_length {
+                result.description.truncate(floor_char_boundary(&result.description, crop_length));
+                result.description.push('…');
+            }
+        }
+
+        if query.highlight {
+            result.description = highlight_terms(&result.description, &terms);
+        }
+    }
+}
+
+/// Largest byte index `<= index` that lands on a UTF-8 char boundary, so cropping a
+/// snippet to a byte count can't split a multi-byte character and panic `truncate`.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    (0..=index).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0)
+}
+
+fn highlight_terms(text: &str, terms: &[String]) -> String {
+    let mut highlighted = text.to_string();
+    for term in terms {
+        if term.is_empty() {
+            continue;
+        }
+        highlighted = highlight_term(&highlighted, term);
+    }
+    highlighted
+}
+
+/// Wraps case-insensitive occurrences of `term` in `**markers**`, preserving the
+/// original casing of the matched substring.
+///
+/// Matches char-by-char rather than lowercasing the whole string and searching in it:
+/// `str::to_lowercase()` can change a character's byte length (e.g. Turkish `İ` is 2
+/// bytes but lowercases to 3), so byte offsets found in a separately-lowercased copy
+/// don't line up with the original string's char boundaries and can split a multi-byte
+/// character when sliced.
+fn highlight_term(text: &str, term: &str) -> String {
+    let term_chars: Vec<char> = term.chars().collect();
+    let text_chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    let mut result = String::with_capacity(text.len());
+    let mut copied_up_to = 0;
+    let mut i = 0;
+    while i < text_chars.len() {
+        if matches_case_insensitive_at(&text_chars, i, &term_chars) {
+            let start_byte = text_chars[i].0;
+            let end_index = i + term_chars.len();
+            let end_byte = text_chars.get(end_index).map_or(text.len(), |(b, _)| *b);
+
+            result.push_str(&text[copied_up_to..start_byte]);
+            result.push_str("**");
+            result.push_str(&text[start_byte..end_byte]);
+            result.push_str("**");
+
+            copied_up_to = end_byte;
+            i = end_index;
+        } else {
+            i += 1;
+        }
+    }
+    result.push_str(&text[copied_up_to..]);
+    result
+}
+
+fn matches_case_insensitive_at(text_chars: &[(usize, char)], start: usize, term_chars: &[char]) -> bool {
+    if start + term_chars.len() > text_chars.len() {
+        return false;
+    }
+    text_chars[start..start + term_chars.len()]
+        .iter()
+        .zip(term_chars.iter())
+        .all(|(&(_, tc), &pc)| tc.to_lowercase().eq(pc.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_terms_wraps_case_insensitive_matches() {
+        let highlighted = highlight_terms("The Quick Fox", &["quick".to_string()]);
+        assert_eq!(highlighted, "The **Quick** Fox");
+    }
+
+    #[test]
+    fn highlight_terms_does_not_panic_on_multi_byte_lowercase_expansion() {
+        // Turkish İ (U+0130, 2 bytes) lowercases to "i̇" (3 bytes): a naive
+        // lowercase-the-whole-string-then-slice-the-original approach panics here.
+        let text = "Visiting İstanbul this summer was great";
+        let highlighted = highlight_terms(text, &["i".to_string()]);
+        assert!(highlighted.contains("**i**"));
+    }
+
+    #[test]
+    fn highlight_terms_handles_multi_byte_text_around_the_match() {
+        let highlighted = highlight_terms("café Rust", &["rust".to_string()]);
+        assert_eq!(highlighted, "café **Rust**");
+    }
+
+    #[test]
+    fn floor_char_boundary_does_not_split_a_multi_byte_char() {
+        let s = "café"; // 'é' is 2 bytes, starting at byte index 3
+        assert_eq!(floor_char_boundary(s, 4), 3);
+        assert_eq!(floor_char_boundary(s, 100), s.len());
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+fn default_count() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Freshness {
+    Day,
+    Week,
+    Month,
+}
+
+impl Freshness {
+    /// Brave's two-letter freshness codes (`pd`/`pw`/`pm`).
+    pub fn as_brave_code(&self) -> &'static str {
+        match self {
+            Freshness::Day => "pd",
+            Freshness::Week => "pw",
+            Freshness::Month => "pm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SafeSearch {
+    Off,
+    #[default]
+    Moderate,
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultType {
+    Web,
+    News,
+    Images,
+    Videos,
+}
+
+/// Structured search parameters, replacing the old loose `query`/`limit`/`api_key` trio
+/// so the agent can control recency, volume, and result shape instead of getting a
+/// fixed top-5 web-only lookup.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub search_lang: Option<String>,
+    #[serde(default)]
+    pub freshness: Option<Freshness>,
+    #[serde(default)]
+    pub safesearch: SafeSearch,
+    #[serde(default)]
+    pub result_types: Vec<ResultType>,
+    #[serde(default)]
+    pub highlight: bool,
+    #[serde(default)]
+    pub crop_length: Option<usize>,
+}
+
+impl SearchQuery {
+    /// A minimal query for call sites that only have a bare string and a result count;
+    /// every other field takes its default.
+    pub fn simple(q: impl Into<String>, count: u32) -> Self {
+        Self {
+            q: q.into(),
+            offset: 0,
+            count,
+            country: None,
+            search_lang: None,
+            freshness: None,
+            safesearch: SafeSearch::default(),
+            result_types: Vec::new(),
+            highlight: false,
+            crop_length: None,
+        }
+    }
+}
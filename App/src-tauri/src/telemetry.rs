@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use tauri::{AppHandle, Emitter};
+
+/// Tauri event emitted by [`start_sampler`] on every tick.
+pub const TELEMETRY_EVENT: &str = "telemetry://sample";
+
+/// Default interval between background telemetry samples.
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+static SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new_all()));
+
+/// Generation counter for the background sampler thread. Each [`start_sampler`] call
+/// bumps this and captures the new value; the running loop exits as soon as it sees a
+/// generation other than its own, so a repeat call (window reload, multiple windows,
+/// reconnect) replaces the prior sampler instead of leaking another thread emitting
+/// duplicate events alongside it.
+static SAMPLER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OsInfo {
+    pub os_type: String,
+    pub os_release: String,
+    pub hostname: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MemoryInfo {
+    pub total_mem_bytes: u64,
+    pub free_mem_bytes: u64,
+    pub used_mem_bytes: u64,
+    pub total_swap_bytes: u64,
+    pub used_swap_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Telemetry {
+    pub memory: MemoryInfo,
+    pub cpu_usage_percent: f32,
+    pub per_core_usage_percent: Vec<f32>,
+    pub logical_cpu_count: usize,
+}
+
+/// Detects the logical thread count the way parallel scanners do: trust the OS-reported
+/// parallelism first, falling back to `num_cpus` when it is unset.
+pub fn logical_cpu_count() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or_else(|_| num_cpus::get())
+}
+
+/// Cross-platform OS info, replacing the old `sw_vers`/`cat`/`hostname` subprocess calls.
+pub fn os_info() -> OsInfo {
+    OsInfo {
+        os_type: System::name().unwrap_or_else(|| "Unknown".to_string()),
+        os_release: System::os_version().unwrap_or_else(|| "Unknown".to_string()),
+        hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
+    }
+}
+
+fn memory_info() -> MemoryInfo {
+    let mut system = SYSTEM.lock().unwrap();
+    system.refresh_memory();
+    MemoryInfo {
+        total_mem_bytes: system.total_memory(),
+        free_mem_bytes: system.free_memory(),
+        used_mem_bytes: system.used_memory(),
+        total_swap_bytes: system.total_swap(),
+        used_swap_bytes: system.used_swap(),
+    }
+}
+
+fn cpu_snapshot() -> (f32, Vec<f32>) {
+    let mut system = SYSTEM.lock().unwrap();
+    system.refresh_cpu_usage();
+    let per_core: Vec<f32> = system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+    let aggregate = if per_core.is_empty() {
+        0.0
+    } else {
+        per_core.iter().sum::<f32>() / per_core.len() as f32
+    };
+    (aggregate, per_core)
+}
+
+/// Takes a one-shot snapshot of memory, CPU load, and thread count.
+pub fn snapshot() -> Telemetry {
+    let memory = memory_info();
+    let (cpu_usage_percent, per_core_usage_percent) = cpu_snapshot();
+    Telemetry {
+        memory,
+        cpu_usage_percent,
+        per_core_usage_percent,
+        logical_cpu_count: logical_cpu_count(),
+    }
+}
+
+/// Spawns a background thread that emits a [`Telemetry`] snapshot via `TELEMETRY_EVENT`
+/// on every tick, so the frontend can render a live resource monitor while the agent
+/// runs long commands.
+///
+/// Calling this again (e.g. on a window reload or from a second window) replaces the
+/// previous sampler rather than leaking another thread: the new call claims the next
+/// generation, and the old loop notices it's no longer current and exits on its next
+/// tick. Call [`stop_sampler`] to stop sampling without starting a replacement.
+pub fn start_sampler(app_handle: AppHandle, interval: Duration) {
+    let generation = SAMPLER_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    thread::spawn(move || loop {
+        if SAMPLER_GENERATION.load(Ordering::SeqCst) != generation {
+            break;
+        }
+        let telemetry = snapshot();
+        if let Err(e) = app_handle.emit(TELEMETRY_EVENT, &telemetry) {
+            eprintln!("Failed to emit telemetry sample: {}", e);
+        }
+        thread::sleep(interval);
+    });
+}
+
+/// Stops the current background sampler (if any) started by [`start_sampler`], without
+/// starting a replacement.
+pub fn stop_sampler() {
+    SAMPLER_GENERATION.fetch_add(1, Ordering::SeqCst);
+}